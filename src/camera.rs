@@ -1,7 +1,4 @@
-use {
-    crate::types::{Ray, Vec3},
-    rand::Rng,
-};
+use crate::types::{Ray, Sampler, Vec3};
 
 pub struct Camera {
     origin: Vec3,
@@ -61,9 +58,9 @@ impl Camera {
         }
     }
 
-    pub fn get_ray(&self, u: f64, v: f64) -> Ray {
-        let mut rng = rand::thread_rng();
-        let rd = random_in_unit_disk(&mut rng) * self.lens_radius;
+    /// The point on the lens comes from the next 2D sample of `sampler`
+    pub fn get_ray(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Ray {
+        let rd = random_in_unit_disk(sampler.get_2d()) * self.lens_radius;
         let offset = self.u * rd.x() + self.v * rd.y();
         Ray::new(
             self.origin + offset,
@@ -72,11 +69,21 @@ impl Camera {
     }
 }
 
-fn random_in_unit_disk(rng: &mut rand::rngs::ThreadRng) -> Vec3 {
-    let mut p = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), 0.0) * 2.0 - Vec3::new(1.0, 1.0, 0.0);
-
-    while p.dot(&p) >= 1.0 {
-        p = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), 0.0) * 2.0 - Vec3::new(1.0, 0.0, 0.0);
+// Shirley and Chiu's concentric mapping from the unit square to the unit disk.
+// It keeps samples that were spread out over the square spread out over the disk
+fn random_in_unit_disk((u1, u2): (f64, f64)) -> Vec3 {
+    let (a, b) = (u1 * 2.0 - 1.0, u2 * 2.0 - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return Vec3::new(0.0, 0.0, 0.0);
     }
-    p
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, std::f64::consts::FRAC_PI_4 * (b / a))
+    } else {
+        (
+            b,
+            std::f64::consts::FRAC_PI_2 - std::f64::consts::FRAC_PI_4 * (a / b),
+        )
+    };
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
 }
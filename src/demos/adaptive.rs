@@ -0,0 +1,177 @@
+use {
+    super::{Chunk, RenderOptions},
+    crate::types::{spectrum, Vec3},
+};
+
+/// Running totals of the samples a pixel has taken
+#[derive(Clone, Copy)]
+pub struct PixelStats {
+    sum: Vec3,
+    /// sum of the luminance of each sample and of it's square, For the variance
+    luminance: f64,
+    luminance_sq: f64,
+    pub samples: u32,
+}
+
+impl PixelStats {
+    pub fn new() -> Self {
+        Self {
+            sum: Vec3::new(0.0, 0.0, 0.0),
+            luminance: 0.0,
+            luminance_sq: 0.0,
+            samples: 0,
+        }
+    }
+
+    pub fn add(&mut self, color: Vec3) {
+        let luminance = spectrum::luminance(color);
+        self.sum += color;
+        self.luminance += luminance;
+        self.luminance_sq += luminance * luminance;
+        self.samples += 1;
+    }
+
+    pub fn mean(&self) -> Vec3 {
+        if self.samples == 0 {
+            return Vec3::new(0.0, 0.0, 0.0);
+        }
+        self.sum / self.samples as f64
+    }
+
+    /// Half width of the 95% confidence interval of the pixel's brightness after gamma
+    /// correction, In 0..1 display units. Noise in dark pixels is easier to see so the
+    /// interval is scaled by the slope of the square root at the mean
+    fn error(&self) -> f64 {
+        let n = self.samples as f64;
+        if self.samples < 2 {
+            return f64::INFINITY;
+        }
+        let mean = self.luminance / n;
+        let variance = ((self.luminance_sq - mean * self.luminance) / (n - 1.0)).max(0.0);
+
+        1.96 * (variance / n).sqrt() / (2.0 * mean.max(1e-4).sqrt())
+    }
+}
+
+/// Samples left to hand out over the whole image.
+///
+/// Every pixel starts with a few samples, Then the budget goes a batch at a time to the
+/// pixels that are still too noisy, Noisiest first. Pixels in flat areas like the sky
+/// stop early and leave their share to edges, glossy reflections and caustics.
+/// Without adaptive sampling, Every pixel takes all of its samples in the first round.
+///
+/// Progressive renders take a sample per pixel in every pass instead, Until the pixel
+/// has `samples` of them or has converged
+pub struct Budget {
+    remaining: u64,
+    /// samples per pixel, And the most a pixel takes when rendering progressively
+    samples: u32,
+    first: u32,
+    batch: u32,
+    /// No pixel takes more than this many samples
+    max: u32,
+    threshold: Option<f64>,
+}
+
+impl Budget {
+    pub fn new(options: &RenderOptions, pixels: usize) -> Self {
+        let samples = options.samples.max(1);
+        let total = samples as u64 * pixels as u64;
+
+        match options.adaptive {
+            Some(threshold) => Self {
+                remaining: total,
+                samples,
+                first: (samples / 4).max(8).min(samples),
+                batch: (samples / 8).max(4),
+                max: samples * 8,
+                threshold: Some(threshold),
+            },
+            None => Self {
+                remaining: total,
+                samples,
+                first: samples,
+                batch: 0,
+                max: samples,
+                threshold: None,
+            },
+        }
+    }
+
+    pub fn first_round(&mut self, chunks: &mut [Chunk]) {
+        for chunk in chunks {
+            for pending in &mut chunk.pending {
+                *pending = self.first;
+            }
+            self.remaining = self
+                .remaining
+                .saturating_sub(self.first as u64 * chunk.pending.len() as u64);
+        }
+    }
+
+    fn converged(&self, pixel: &PixelStats) -> bool {
+        match self.threshold {
+            Some(threshold) => pixel.samples >= self.first && pixel.error() <= threshold,
+            None => false,
+        }
+    }
+
+    /// Plans the samples of the next round. false once every pixel is done or the
+    /// budget ran out
+    pub fn next_round(&mut self, chunks: &mut [Chunk]) -> bool {
+        if self.remaining == 0 || self.batch == 0 {
+            return false;
+        }
+
+        // Pixels with no samples at all belong to demos that draw without sampling
+        let mut noisy: Vec<(f64, usize, usize)> = Vec::new();
+        for (c, chunk) in chunks.iter().enumerate() {
+            for (p, pixel) in chunk.pixels.iter().enumerate() {
+                if pixel.samples > 0 && pixel.samples < self.max && !self.converged(pixel) {
+                    noisy.push((pixel.error(), c, p));
+                }
+            }
+        }
+        if noisy.is_empty() {
+            return false;
+        }
+
+        // Stable sort, So ties always go the same way and renders stay deterministic
+        noisy.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        for (_, c, p) in noisy {
+            if self.remaining == 0 {
+                break;
+            }
+            let chunk = &mut chunks[c];
+            let samples = self
+                .batch
+                .min(self.max - chunk.pixels[p].samples)
+                .min(self.remaining.min(u32::MAX as u64) as u32);
+            chunk.pending[p] = samples;
+            self.remaining -= samples as u64;
+        }
+        true
+    }
+
+    pub fn first_pass(&self, chunks: &mut [Chunk]) {
+        for chunk in chunks {
+            for pending in &mut chunk.pending {
+                *pending = 1;
+            }
+        }
+    }
+
+    /// Plans one more sample for every pixel that isn't done, false once they all are
+    pub fn next_pass(&self, chunks: &mut [Chunk]) -> bool {
+        let mut planned = false;
+        for chunk in chunks {
+            for (pixel, pending) in chunk.pixels.iter().zip(&mut chunk.pending) {
+                if pixel.samples > 0 && pixel.samples < self.samples && !self.converged(pixel) {
+                    *pending = 1;
+                    planned = true;
+                }
+            }
+        }
+        planned
+    }
+}
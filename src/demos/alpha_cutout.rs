@@ -0,0 +1,161 @@
+use {
+    crate::{
+        demos::{Chunk, Demo},
+        types::{
+            material::{Cutout, Lambertian},
+            texture::{CheckerTexture, ConstantTexture},
+            Hitable, HitableList, Ray, Rectangle, Sampler, Sphere, Texture, Vec3,
+        },
+        Camera,
+    },
+    std::f64::consts::PI,
+};
+
+pub struct AlphaCutout;
+
+impl Demo for AlphaCutout {
+    fn name(&self) -> &'static str {
+        "alpha-cutout"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let leaf = |corner: Vec3, u: Vec3, v: Vec3, color: Vec3| -> Box<dyn Hitable> {
+            Box::new(Rectangle::with_material(
+                corner,
+                u,
+                v,
+                Box::new(
+                    Cutout::new(Box::new(Lambertian::new(color)), Box::new(LeafMask))
+                        .alpha_test(0.5),
+                ),
+            ))
+        };
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                // fence made from a single rectangle
+                Box::new(Rectangle::with_material(
+                    Vec3::new(-2.5, -0.5, -2.6),
+                    Vec3::new(5.0, 0.0, 0.0),
+                    Vec3::new(0.0, 1.2, 0.0),
+                    Box::new(
+                        Cutout::new(
+                            Box::new(Lambertian::new(Vec3::new(0.8, 0.8, 0.75))),
+                            Box::new(CheckerTexture::new(
+                                Vec3::new(0.0, 0.0, 0.0),
+                                Vec3::new(1.0, 1.0, 1.0),
+                                4.0,
+                            )),
+                        )
+                        .alpha_test(0.5),
+                    ),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.8, 0.0, -3.2),
+                    0.5,
+                    Box::new(Lambertian::new(Vec3::new(0.8, 0.4, 0.1))),
+                )),
+                // ghost, half of the rays go right through it
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.2, 0.0, -1.6),
+                    0.45,
+                    Box::new(Cutout::new(
+                        Box::new(Lambertian::new(Vec3::new(0.2, 0.3, 0.8))),
+                        Box::new(ConstantTexture::new(Vec3::new(0.4, 0.4, 0.4))),
+                    )),
+                )),
+                leaf(
+                    Vec3::new(-1.6, -0.45, -1.5),
+                    Vec3::new(0.4, 0.0, 0.1),
+                    Vec3::new(0.05, 0.7, -0.2),
+                    Vec3::new(0.2, 0.5, 0.1),
+                ),
+                leaf(
+                    Vec3::new(-0.9, -0.2, -1.3),
+                    Vec3::new(0.35, 0.2, 0.0),
+                    Vec3::new(-0.3, 0.525, 0.1),
+                    Vec3::new(0.35, 0.55, 0.1),
+                ),
+                leaf(
+                    Vec3::new(-0.2, -0.4, -1.7),
+                    Vec3::new(0.3, 0.0, -0.3),
+                    Vec3::new(0.15, 0.6, 0.15),
+                    Vec3::new(0.6, 0.45, 0.1),
+                ),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
+
+/// Leaf shaped opacity mask. The leaf runs along v with the stem at v = 0
+struct LeafMask;
+
+impl Texture for LeafMask {
+    fn value(&self, u: f64, v: f64, _p: Vec3) -> Vec3 {
+        let distance = (u - 0.5).abs();
+        let half_width = 0.45 * (PI * v).sin().powf(0.7) * (1.0 - 0.4 * v);
+        let stem = v < 0.15 && distance < 0.02;
+
+        if distance < half_width || stem {
+            Vec3::new(1.0, 1.0, 1.0)
+        } else {
+            Vec3::new(0.0, 0.0, 0.0)
+        }
+    }
+}
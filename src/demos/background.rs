@@ -0,0 +1,133 @@
+use {
+    super::{Demo, Render, RenderOptions},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc::{self, Receiver},
+            Arc,
+        },
+        thread,
+    },
+};
+
+/// Tells a render to stop. Clones share the same flag, Workers check it before every
+/// chunk and every sample so a cancelled render stops almost right away
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A chunk of the image that finished rendering
+pub struct Tile {
+    /// position of the top left corner of the tile in the frame buffer
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    /// RGBA pixels, Top row first like the frame buffer
+    pub pixels: Vec<u8>,
+}
+
+impl Tile {
+    /// Copies the tile into its place in `buf`, A frame buffer `width` pixels wide
+    pub fn draw(&self, buf: &mut [u8], width: usize) {
+        for (row, line) in self.pixels.chunks(self.width * 4).enumerate() {
+            let offset = ((self.y + row) * width + self.x) * 4;
+            buf[offset..offset + line.len()].copy_from_slice(line);
+        }
+    }
+}
+
+pub enum Update {
+    /// A chunk finished a round or a pass
+    Tile(Tile),
+    /// The whole image after post processing, Sent when a render or a progressive
+    /// pass finishes, With the samples taken by every pixel so far
+    Frame {
+        pixels: Vec<u8>,
+        sample_counts: Vec<u32>,
+    },
+}
+
+/// Renders on a thread of its own and sends the image over a channel as it comes along,
+/// So the main loop can keep drawing it and handling events in the meantime.
+/// The thread is done once the channel disconnects. Dropping the render cancels it
+pub struct BackgroundRender {
+    updates: Receiver<Update>,
+    cancel: CancellationToken,
+}
+
+impl BackgroundRender {
+    pub fn spawn(
+        demo: &'static dyn Demo,
+        width: usize,
+        height: usize,
+        options: RenderOptions,
+        progressive: bool,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+
+        thread::spawn(move || {
+            let mut render = Render::new(demo, width, height, options)
+                .send_updates(tx.clone())
+                .cancel_with(token.clone());
+            let mut buf = vec![0; width * height * 4];
+
+            if progressive {
+                while render.pass() {
+                    render.write(&mut buf);
+                    let frame = Update::Frame {
+                        pixels: buf.clone(),
+                        sample_counts: render.sample_counts(),
+                    };
+                    if tx.send(frame).is_err() {
+                        return;
+                    }
+                }
+            } else {
+                render.finish();
+                if token.is_cancelled() {
+                    return;
+                }
+                render.write(&mut buf);
+                tx.send(Update::Frame {
+                    pixels: buf,
+                    sample_counts: render.sample_counts(),
+                })
+                .ok();
+            }
+        });
+
+        Self {
+            updates: rx,
+            cancel,
+        }
+    }
+
+    pub fn updates(&self) -> &Receiver<Update> {
+        &self.updates
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for BackgroundRender {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{
+        demos::{Chunk, Demo},
+        types::{
+            material::{DiffuseLight, Lambertian},
+            Hitable, HitableList, Ray, Rectangle, Sampler, Sphere, Vec3,
+        },
+        Camera,
+    },
+    std::f64::consts::PI,
+};
+
+pub struct BlackbodyLights;
+
+impl Demo for BlackbodyLights {
+    fn name(&self) -> &'static str {
+        "blackbody-lights"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let bulb = |x: f64, kelvin: f64| -> Box<dyn Hitable> {
+            let radius = 0.1;
+            let area = 4.0 * PI * radius * radius;
+            Box::new(Sphere::with_material(
+                Vec3::new(x, 0.15, -1.2),
+                radius,
+                Box::new(DiffuseLight::lamp(kelvin, 800.0, area)),
+            ))
+        };
+        let white = || Box::new(Lambertian::new(Vec3::new(0.8, 0.8, 0.8)));
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    white(),
+                )),
+                Box::new(Rectangle::with_material(
+                    Vec3::new(-3.0, -0.5, -2.0),
+                    Vec3::new(6.0, 0.0, 0.0),
+                    Vec3::new(0.0, 3.0, 0.0),
+                    white(),
+                )),
+                // candle, incandescent bulb, daylight and clear blue sky
+                bulb(-1.5, 1900.0),
+                bulb(-0.5, 3200.0),
+                bulb(0.5, 6500.0),
+                bulb(1.5, 12000.0),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.0, -0.25, -1.2),
+                    0.25,
+                    white(),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -0.25, -1.2),
+                    0.25,
+                    white(),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.0, -0.25, -1.2),
+                    0.25,
+                    white(),
+                )),
+                // dim moonlight from above
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, 6.0, 2.0),
+                    2.0,
+                    Box::new(DiffuseLight::blackbody(4100.0, 0.3)),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.5, 1.5);
+        let lookat = Vec3::new(0.0, -0.1, -1.2);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let emitted = material.emitted(&ray, &hit_rec);
+        if depth >= 50 {
+            emitted
+        } else if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler)
+        {
+            emitted + calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+        } else {
+            emitted
+        }
+    } else {
+        // night sky
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+}
@@ -1,10 +1,7 @@
-use {
-    crate::{
-        demos::{Chunk, Demo},
-        types::{Hitable, HitableList, Ray, Sphere, Vec3},
-        Camera,
-    },
-    rand::Rng,
+use crate::{
+    demos::{Chunk, Demo},
+    types::{Hitable, HitableList, Ray, Sampler, Sphere, Vec3},
+    Camera,
 };
 
 pub struct DiffuseMaterials;
@@ -28,58 +25,31 @@ impl Demo for DiffuseMaterials {
         chunk: &mut Chunk,
         camera: Option<&Camera>,
         world: Option<&HitableList>,
-        samples: u8,
     ) {
-        let &mut Chunk {
-            x,
-            y,
-            nx,
-            ny,
-            start_x,
-            start_y,
-            ref mut buffer,
-        } = chunk;
         let camera = camera.unwrap();
         let world = world.unwrap();
 
-        let mut rng = rand::thread_rng();
-        let mut offset = 0;
-        for j in start_y..start_y + ny {
-            for i in start_x..start_x + nx {
-                let mut color = Vec3::new(0.0, 0.0, 0.0);
-
-                for _s in 0..samples {
-                    let u = (i as f64 + rng.gen::<f64>()) / x as f64;
-                    let v = (j as f64 + rng.gen::<f64>()) / y as f64;
-                    let r = camera.get_ray(u, v);
-                    color += calc_color(r, &world, &mut rng);
-                }
-
-                color /= samples as f64;
-
-                // Without taking square root of each color, we get a picture that
-                // is quite dark
-                // Spheres in this case are absorbing 50% of the light casted on them
-                // So, IRL, It *should* look a bit lighter in color
-                // To do that, We apply gamma correction by a factor of 2
-                // which means multiple rgb values by 1/gamma aka 1/2
-                buffer[offset] = (255.99 * color.r().sqrt()) as u8;
-                buffer[offset + 1] = (255.99 * color.g().sqrt()) as u8;
-                buffer[offset + 2] = (255.99 * color.b().sqrt()) as u8;
-                offset += 4;
-            }
-        }
+        // Without taking square root of each color, we get a picture that
+        // is quite dark
+        // Spheres in this case are absorbing 50% of the light casted on them
+        // So, IRL, It *should* look a bit lighter in color
+        // To do that, `sample` applies gamma correction by a factor of 2
+        // which means multiple rgb values by 1/gamma aka 1/2
+        chunk.sample(|u, v, sampler| {
+            let r = camera.get_ray(u, v, sampler);
+            calc_color(r, &world, sampler)
+        });
     }
 }
 
-fn calc_color(ray: Ray, world: &HitableList, rng: &mut rand::rngs::ThreadRng) -> Vec3 {
+fn calc_color(ray: Ray, world: &HitableList, sampler: &mut dyn Sampler) -> Vec3 {
     // The value of t_min here could've been 0.0 but since f32/f64 can only be
     // partially compared, It may cause shadow acne effect.
     // To combat this problem, We set a bias
     // More information here, https://www.opengl-tutorial.org/intermediate-tutorials/tutorial-16-shadow-mapping/#shadow-acne
     if let Some(hit_rec) = world.hit(&ray, 0.001, std::f64::MAX) {
-        let target = hit_rec.p + hit_rec.normal + random_point_in_unit_sphere(rng);
-        calc_color(Ray::new(hit_rec.p, target - hit_rec.p), &world, rng) * 0.5
+        let target = hit_rec.p + hit_rec.normal + random_point_in_unit_sphere(sampler);
+        calc_color(Ray::new(hit_rec.p, target - hit_rec.p), &world, sampler) * 0.5
     } else {
         let unit_direction = ray.direction().unit_vector();
         let t = 0.5 * (unit_direction.y() + 1.0);
@@ -87,12 +57,14 @@ fn calc_color(ray: Ray, world: &HitableList, rng: &mut rand::rngs::ThreadRng) ->
     }
 }
 
-fn random_point_in_unit_sphere(rng: &mut rand::rngs::ThreadRng) -> Vec3 {
-    let mut point = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 2.0
-        - Vec3::new(1.0, 1.0, 1.0);
-    while point.sq_len() >= 1.0 {
-        point = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 2.0
-            - Vec3::new(1.0, 1.0, 1.0);
-    }
-    point
+// Uniform direction scaled by the cube root of a uniform number, Which spreads the
+// points evenly through the sphere while always taking the same three dimensions
+fn random_point_in_unit_sphere(sampler: &mut dyn Sampler) -> Vec3 {
+    let (u1, u2) = sampler.get_2d();
+    let radius = sampler.get_1d().cbrt();
+
+    let z = 1.0 - 2.0 * u1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * std::f64::consts::PI * u2;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z) * radius
 }
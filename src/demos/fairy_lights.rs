@@ -0,0 +1,181 @@
+use {
+    crate::{
+        demos::{Chunk, Demo},
+        types::{
+            light::{LightBvh, SphereLight},
+            material::{Conductor, DiffuseLight, Lambertian, Principled},
+            Hitable, HitableList, Light, Ray, Sampler, Sphere, Vec3,
+        },
+        Camera,
+    },
+    std::f64::consts::PI,
+};
+
+pub struct FairyLights;
+
+impl FairyLights {
+    fn lights(&self) -> Vec<Box<dyn Light>> {
+        let bulbs = bulbs()
+            .into_iter()
+            .map(|(center, radius, emit)| {
+                Box::new(SphereLight::new(center, radius, emit)) as Box<dyn Light>
+            })
+            .collect();
+
+        vec![Box::new(LightBvh::new(bulbs))]
+    }
+}
+
+impl Demo for FairyLights {
+    fn name(&self) -> &'static str {
+        "fairy-lights"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let mut world = HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.1, 0.0, -1.5),
+                    0.5,
+                    Box::new(Lambertian::new(Vec3::new(0.8, 0.8, 0.8))),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::silver(0.2)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.1, 0.0, -1.5),
+                    0.5,
+                    Box::new(Principled::new(Vec3::new(0.2, 0.4, 0.2)).clearcoat(1.0)),
+                )),
+            ],
+        };
+        for (center, radius, emit) in bulbs() {
+            world.push(Box::new(Sphere::with_material(
+                center,
+                radius,
+                Box::new(DiffuseLight::new(emit)),
+            )));
+        }
+
+        Some(world)
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+        let lights = self.lights();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, &lights, 0, true, sampler)
+        });
+    }
+}
+
+/// `count_emitted` is false when the light from emitters was already
+/// sampled directly at the previous bounce
+fn calc_color(
+    ray: Ray,
+    world: &HitableList,
+    lights: &[Box<dyn Light>],
+    depth: u32,
+    count_emitted: bool,
+    sampler: &mut dyn Sampler,
+) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let mut color = if count_emitted {
+            material.emitted(&ray, &hit_rec)
+        } else {
+            Vec3::new(0.0, 0.0, 0.0)
+        };
+
+        let mut sampled_lights = false;
+        for light in lights {
+            let sample = light.sample(hit_rec.p);
+            if let Some(reflected) = material.eval(&ray, &hit_rec, sample.direction) {
+                sampled_lights = true;
+                // Stop just short of the light so it doesn't block itself
+                let shadow_ray = Ray::new(hit_rec.p, sample.direction);
+                if world
+                    .hit(&shadow_ray, 0.001, sample.distance - 0.001)
+                    .is_none()
+                {
+                    color += reflected * sample.radiance;
+                }
+            }
+        }
+
+        if depth < 50 {
+            if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler) {
+                color += calc_color(
+                    scattered_ray,
+                    world,
+                    lights,
+                    depth + 1,
+                    !sampled_lights,
+                    sampler,
+                ) * attenuation;
+            }
+        }
+        color
+    } else {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+}
+
+/// Strands of small bulbs hanging over the scene. Center, radius and emission of each bulb
+fn bulbs() -> Vec<(Vec3, f64, Vec3)> {
+    let colors = [
+        Vec3::new(1.0, 0.75, 0.4),
+        Vec3::new(1.0, 0.15, 0.1),
+        Vec3::new(0.2, 1.0, 0.3),
+        Vec3::new(0.2, 0.4, 1.0),
+        Vec3::new(1.0, 0.5, 0.05),
+    ];
+    let mut bulbs = Vec::new();
+    for strand in 0..8 {
+        let z = -0.6 - strand as f64 * 0.35;
+        let height = 0.9 + 0.1 * (strand % 3) as f64;
+        for i in 0..50 {
+            let t = i as f64 / 49.0;
+            let x = -2.5 + 5.0 * t;
+            // sagging between the two ends, with a little sideways wiggle
+            let y = height - 0.5 * (PI * t).sin();
+            let wiggle = 0.05 * (37.0 * t + strand as f64).sin();
+            bulbs.push((
+                Vec3::new(x, y, z + wiggle),
+                0.015,
+                colors[(i + strand) % colors.len()] * 40.0,
+            ));
+        }
+    }
+    bulbs
+}
@@ -0,0 +1,141 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::Lambertian, texture::Perlin, HeterogeneousMedium, Hitable, HitableList, Ray,
+        Sampler, Sphere, Vec3, VoxelGrid,
+    },
+    Camera,
+};
+
+pub struct Fire;
+
+impl Demo for Fire {
+    fn name(&self) -> &'static str {
+        "fire"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let noise = Perlin::new(0);
+
+        // Flames get narrower and cooler towards the top
+        let flame_shape = |p: Vec3| {
+            let width = 0.45 * (1.0 - p.y()).powf(0.7);
+            let x = p.x() - 0.5 + 0.08 * noise.noise(Vec3::new(0.0, p.y() * 3.0, 0.0));
+            let z = p.z() - 0.5;
+            let r = (x * x + z * z).sqrt();
+            ((1.0 - r / width.max(1e-3)) + 0.5 * noise.turbulence(p * 6.0, 4) - 0.2).max(0.0)
+        };
+        let flame = VoxelGrid::from_fn(32, 64, 32, |p| flame_shape(p).min(1.0));
+        let flame_temperature = VoxelGrid::from_fn(32, 64, 32, |p| {
+            (flame_shape(p) * (1.0 - 0.5 * p.y())).min(1.0)
+        });
+
+        // Hot core of the explosion with a cooler, smoky shell
+        let fireball_distance = |p: Vec3| (p - Vec3::new(0.5, 0.5, 0.5)).length() * 2.0;
+        let fireball = VoxelGrid::from_fn(48, 48, 48, |p| {
+            (1.0 - fireball_distance(p) + 0.6 * noise.turbulence(p * 5.0, 5)).clamp(0.0, 1.0)
+        });
+        let fireball_temperature = VoxelGrid::from_fn(48, 48, 48, |p| {
+            (1.1 - 0.8 * fireball_distance(p) + 0.4 * noise.turbulence(p * 8.0, 4)).clamp(0.0, 1.0)
+        });
+
+        // Glowing magic mist, Colored directly instead of by temperature
+        let mist = || {
+            VoxelGrid::from_fn(32, 16, 32, |p| {
+                let d = (p - Vec3::new(0.5, 0.5, 0.5)) * 2.0;
+                (1.0 - d.length() + 0.8 * noise.turbulence(p * 4.0, 4) - 0.3).max(0.0)
+            })
+        };
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                Box::new(
+                    HeterogeneousMedium::new(
+                        Box::new(Sphere::new(Vec3::new(-1.0, 0.1, -1.6), 0.75)),
+                        flame,
+                        Vec3::new(-1.4, -0.5, -2.0),
+                        Vec3::new(-0.6, 0.7, -1.2),
+                    )
+                    .density(20.0)
+                    .albedo(Vec3::new(0.2, 0.2, 0.2))
+                    .temperature(flame_temperature, 2200.0, 12.0),
+                ),
+                Box::new(
+                    HeterogeneousMedium::new(
+                        Box::new(Sphere::new(Vec3::new(0.9, 0.4, -2.0), 0.9)),
+                        fireball,
+                        Vec3::new(0.2, -0.3, -2.7),
+                        Vec3::new(1.6, 1.1, -1.3),
+                    )
+                    .density(25.0)
+                    .albedo(Vec3::new(0.5, 0.5, 0.5))
+                    .temperature(fireball_temperature, 3000.0, 20.0),
+                ),
+                Box::new(
+                    HeterogeneousMedium::new(
+                        Box::new(Sphere::new(Vec3::new(0.0, -0.3, -1.0), 0.6)),
+                        mist(),
+                        Vec3::new(-0.5, -0.5, -1.5),
+                        Vec3::new(0.5, 0.0, -0.5),
+                    )
+                    .density(8.0)
+                    .albedo(Vec3::new(0.6, 0.6, 0.6))
+                    .emission(mist(), Vec3::new(0.2, 1.5, 0.8)),
+                ),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.1, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            60.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let emitted = material.emitted(&ray, &hit_rec);
+        if depth >= 50 {
+            emitted
+        } else if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler)
+        {
+            emitted + calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+        } else {
+            emitted
+        }
+    } else {
+        // night sky
+        Vec3::new(0.01, 0.01, 0.03)
+    }
+}
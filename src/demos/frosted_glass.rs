@@ -0,0 +1,113 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::{Dielectric, Lambertian, RoughDielectric},
+        Hitable, HitableList, Ray, Sampler, Sphere, Vec3,
+    },
+    Camera,
+};
+
+pub struct FrostedGlass;
+
+impl Demo for FrostedGlass {
+    fn name(&self) -> &'static str {
+        "frosted-glass"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let mut world = HitableList {
+            list: vec![Box::new(Sphere::with_material(
+                Vec3::new(0.0, -100.5, -1.0),
+                100.0,
+                Box::new(Lambertian::new(Vec3::new(0.8, 0.8, 0.8))),
+            ))],
+        };
+
+        // Row of small colorful spheres behind the glass to show off the blur
+        for i in 0..9 {
+            let a = i as f64;
+            world.push(Box::new(Sphere::with_material(
+                Vec3::new(-2.4 + a * 0.6, -0.3, -3.0),
+                0.2,
+                Box::new(Lambertian::new(Vec3::new(
+                    0.5 + 0.5 * (a * 0.7).cos(),
+                    0.5 + 0.5 * (a * 0.7 + 2.1).cos(),
+                    0.5 + 0.5 * (a * 0.7 + 4.2).cos(),
+                ))),
+            )));
+        }
+
+        world.push(Box::new(Sphere::with_material(
+            Vec3::new(-1.1, 0.0, -1.5),
+            0.5,
+            Box::new(Dielectric::new(1.5)),
+        )));
+        world.push(Box::new(Sphere::with_material(
+            Vec3::new(0.0, 0.0, -1.5),
+            0.5,
+            Box::new(RoughDielectric::new(1.5, 0.15)),
+        )));
+        world.push(Box::new(Sphere::with_material(
+            Vec3::new(1.1, 0.0, -1.5),
+            0.5,
+            Box::new(RoughDielectric::with_tint(
+                1.5,
+                0.4,
+                Vec3::new(0.85, 0.95, 0.9),
+            )),
+        )));
+
+        Some(world)
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
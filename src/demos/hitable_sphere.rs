@@ -24,7 +24,6 @@ impl Demo for HitableSphere {
         chunk: &mut Chunk,
         _camera: Option<&Camera>,
         world: Option<&HitableList>,
-        _samples: u8,
     ) {
         let &mut Chunk {
             x,
@@ -34,6 +33,7 @@ impl Demo for HitableSphere {
             start_x,
             start_y,
             ref mut buffer,
+            ..
         } = chunk;
         let world = world.unwrap();
 
@@ -16,7 +16,6 @@ impl Demo for LinearGradientRectangle {
         chunk: &mut Chunk,
         _camera: Option<&Camera>,
         _world: Option<&HitableList>,
-        _samples: u8,
     ) {
         let &mut Chunk {
             x,
@@ -26,6 +25,7 @@ impl Demo for LinearGradientRectangle {
             start_x,
             start_y,
             ref mut buffer,
+            ..
         } = chunk;
 
         // -2.0 and 4.0 in lower_left_corner and horizontal respectively
@@ -1,13 +1,10 @@
-use {
-    crate::{
-        demos::{Chunk, Demo},
-        types::{
-            material::{Lambertian, Metal},
-            Hitable, HitableList, Ray, Sphere, Vec3,
-        },
-        Camera,
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::{Lambertian, Metal},
+        Hitable, HitableList, Ray, Sampler, Sphere, Vec3,
     },
-    rand::Rng,
+    Camera,
 };
 
 pub struct Materials;
@@ -49,53 +46,27 @@ impl Demo for Materials {
         chunk: &mut Chunk,
         camera: Option<&Camera>,
         world: Option<&HitableList>,
-        samples: u8,
     ) {
-        let &mut Chunk {
-            x,
-            y,
-            nx,
-            ny,
-            start_x,
-            start_y,
-            ref mut buffer,
-        } = chunk;
         let camera = camera.unwrap();
 
-        let mut rng = rand::thread_rng();
-        let mut offset = 0;
-
-        for j in start_y..start_y + ny {
-            for i in start_x..start_x + nx {
-                let mut color = Vec3::new(0.0, 0.0, 0.0);
-                for _s in 0..samples {
-                    let u = (i as f64 + rng.gen::<f64>()) / x as f64;
-                    let v = (j as f64 + rng.gen::<f64>()) / y as f64;
-
-                    let ray = camera.get_ray(u, v);
-                    color += calc_color(ray, world.unwrap(), 0);
-                }
-
-                color /= samples as f64;
-
-                // gamma 2 corrected
-                buffer[offset] = (255.99 * color.r().sqrt()) as u8;
-                buffer[offset + 1] = (255.99 * color.g().sqrt()) as u8;
-                buffer[offset + 2] = (255.99 * color.b().sqrt()) as u8;
-                offset += 4;
-            }
-        }
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world.unwrap(), 0, sampler)
+        });
     }
 }
 
-fn calc_color(ray: Ray, world: &HitableList, depth: u32) -> Vec3 {
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
     if let Some(hit_rec) = world.hit(&ray, 0.001, std::f64::MAX) {
         if depth >= 50 {
             Vec3::new(0.0, 0.0, 0.0)
         } else {
             let material = hit_rec.material.as_ref();
-            if let (attenuation, Some(scattered_ray)) = material.unwrap().scatter(&ray, &hit_rec) {
-                calc_color(scattered_ray, &world, depth + 1) * attenuation
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, &world, depth + 1, sampler) * attenuation
             } else {
                 Vec3::new(0.0, 0.0, 0.0)
             }
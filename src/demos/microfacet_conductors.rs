@@ -0,0 +1,99 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::{Conductor, Lambertian},
+        Hitable, HitableList, Ray, Sampler, Sphere, Vec3,
+    },
+    Camera,
+};
+
+pub struct MicrofacetConductors;
+
+impl Demo for MicrofacetConductors {
+    fn name(&self) -> &'static str {
+        "microfacet-conductors"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.65, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::gold(0.05)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-0.55, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::copper(0.25)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.55, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::aluminium(0.45)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.65, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::silver(0.7)),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.6, 1.2);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::{Lambertian, Metal, MixMaterial},
+        texture::CheckerTexture,
+        Hitable, HitableList, Ray, Sampler, Sphere, Vec3,
+    },
+    Camera,
+};
+
+pub struct MixedMaterials;
+
+impl Demo for MixedMaterials {
+    fn name(&self) -> &'static str {
+        "mixed-materials"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                // dusty metal
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.1, 0.0, -1.5),
+                    0.5,
+                    Box::new(MixMaterial::new(
+                        Box::new(Metal::with_fuzz(Vec3::new(0.8, 0.6, 0.2), 0.05)),
+                        Box::new(Lambertian::new(Vec3::new(0.55, 0.5, 0.45))),
+                        0.4,
+                    )),
+                )),
+                // partially painted metal
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, 0.0, -1.5),
+                    0.5,
+                    Box::new(MixMaterial::with_mask(
+                        Box::new(Metal::with_fuzz(Vec3::new(0.8, 0.8, 0.8), 0.1)),
+                        Box::new(Lambertian::new(Vec3::new(0.7, 0.1, 0.1))),
+                        Box::new(CheckerTexture::new(
+                            Vec3::new(0.0, 0.0, 0.0),
+                            Vec3::new(1.0, 1.0, 1.0),
+                            4.0,
+                        )),
+                    )),
+                )),
+                // glossy plastic
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.1, 0.0, -1.5),
+                    0.5,
+                    Box::new(MixMaterial::fresnel(
+                        Box::new(Lambertian::new(Vec3::new(0.1, 0.2, 0.6))),
+                        Box::new(Metal::new(Vec3::new(1.0, 1.0, 1.0))),
+                        1.5,
+                    )),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
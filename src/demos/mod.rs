@@ -1,42 +1,94 @@
+mod adaptive;
+mod alpha_cutout;
+mod anisotropic_metal;
+mod background;
+mod blackbody_lights;
+mod coated_materials;
 mod defocus_blur;
 mod dielectric_material;
 mod diffuse_materials;
+mod fairy_lights;
 mod final_scene;
+mod fire;
+mod frosted_glass;
 mod hitable_sphere;
 mod linear_gradient_rectangle;
 mod materials;
+mod microfacet_conductors;
+mod mixed_materials;
+mod normal_mapping;
+mod outline;
 mod positionable_camera;
+mod principled_bsdf;
+mod rough_diffuse;
 mod simple_antialiasing;
 mod simple_rectangle;
 mod simple_sphere;
+mod spot_lights;
+mod subsurface_scattering;
 mod surface_normal_sphere;
+mod thin_film_iridescence;
+mod toon_shading;
+mod velvet_cloth;
+mod volumes;
 
+pub use alpha_cutout::AlphaCutout;
+pub use anisotropic_metal::AnisotropicMetal;
+pub use background::{BackgroundRender, CancellationToken, Tile, Update};
+pub use blackbody_lights::BlackbodyLights;
+pub use coated_materials::CoatedMaterials;
 pub use defocus_blur::DefocusBlur;
 pub use dielectric_material::DielectricMaterial;
 pub use diffuse_materials::DiffuseMaterials;
+pub use fairy_lights::FairyLights;
 pub use final_scene::FinalScene;
+pub use fire::Fire;
+pub use frosted_glass::FrostedGlass;
 pub use hitable_sphere::HitableSphere;
 pub use linear_gradient_rectangle::LinearGradientRectangle;
 pub use materials::Materials;
+pub use microfacet_conductors::MicrofacetConductors;
+pub use mixed_materials::MixedMaterials;
+pub use normal_mapping::NormalMapping;
 pub use positionable_camera::PositionableCamera;
+pub use principled_bsdf::PrincipledBsdf;
+pub use rough_diffuse::RoughDiffuse;
 pub use simple_antialiasing::SimpleAntialiasing;
 pub use simple_rectangle::SimpleRectangle;
 pub use simple_sphere::SimpleSphere;
+pub use spot_lights::SpotLights;
+pub use subsurface_scattering::SubsurfaceScattering;
 pub use surface_normal_sphere::SurfaceNormalSphere;
+pub use thin_film_iridescence::ThinFilmIridescence;
+pub use toon_shading::ToonShading;
+pub use velvet_cloth::VelvetCloth;
+pub use volumes::Volumes;
 
 use {
     crate::{
-        types::{HitableList, Vec3},
+        types::{sampler::SamplerKind, HitableList, Sampler, Vec3},
         Camera, HORIZONTAL_PARTITION, VERTICAL_PARTITION,
     },
+    adaptive::{Budget, PixelStats},
     rayon::prelude::*,
-    std::{
-        fs::File,
-        io::Write,
-        sync::{Arc, Mutex},
-    },
+    std::{fs::File, io::Write, sync::mpsc::Sender},
 };
 
+/// How a `Render` takes samples
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    /// samples per pixel, On average across the image when sampling adaptively
+    pub samples: u32,
+    pub sampler: SamplerKind,
+    /// renders with the same seed come out exactly the same. It only changes the noise,
+    /// Scenes like the final scene's random spheres are laid out the same for every seed
+    pub seed: u64,
+    /// Pixels stop taking samples once the 95% confidence interval of their brightness
+    /// is narrower than this, In 0..1 display units. `None` gives every pixel the same
+    /// number of samples
+    pub adaptive: Option<f64>,
+}
+
 pub struct Chunk {
     x: usize,
     y: usize,
@@ -44,51 +96,251 @@ pub struct Chunk {
     ny: usize,
     start_x: usize,
     start_y: usize,
+    /// sampler each chunk creates for its pixels
+    sampler: SamplerKind,
+    samples: u32,
+    seed: u64,
+    /// samples taken so far by every pixel of the chunk
+    pixels: Vec<PixelStats>,
+    /// samples every pixel takes the next time the chunk is rendered
+    pending: Vec<u32>,
+    cancel: CancellationToken,
     buffer: Vec<u8>,
 }
 
-pub trait Demo: std::marker::Sync {
-    fn render(&self, buf: &mut Vec<u8>, width: usize, height: usize, samples: u8) {
+impl Chunk {
+    fn new(width: usize, height: usize, i: usize, j: usize, options: &RenderOptions) -> Self {
         let nx = width / VERTICAL_PARTITION;
         let ny = height / HORIZONTAL_PARTITION;
-        let world = self.world();
-        let camera = self.camera(nx as f64 / ny as f64);
-
-        let buf = Arc::new(Mutex::new(buf));
-
-        (0..VERTICAL_PARTITION).into_par_iter().for_each(|j| {
-            let buf = buf.clone();
-            (0..HORIZONTAL_PARTITION).into_par_iter().for_each(|i| {
-                let start_y = j * ny;
-                let start_x = i * nx;
-                let x = width;
-                let y = height;
-                let mut chunk = Chunk {
-                    x,
-                    y,
-                    nx,
-                    ny,
-                    start_x,
-                    start_y,
-                    buffer: vec![0; nx * ny * 4],
-                };
-                self.render_chunk(&mut chunk, camera.as_ref(), world.as_ref(), samples);
-
-                let mut buf = buf.lock().unwrap();
-
-                let mut temp_offset = 0;
-                for j in start_y..start_y + ny {
-                    let real_offset = ((y - j - 1) * x + start_x) * 4;
-
-                    buf[real_offset..real_offset + nx * 4]
-                        .copy_from_slice(&chunk.buffer[temp_offset..temp_offset + nx * 4]);
-
-                    temp_offset += nx * 4;
+        Self {
+            x: width,
+            y: height,
+            nx,
+            ny,
+            start_x: i * nx,
+            start_y: j * ny,
+            sampler: options.sampler,
+            samples: options.samples,
+            seed: options.seed,
+            pixels: vec![PixelStats::new(); nx * ny],
+            pending: vec![0; nx * ny],
+            cancel: CancellationToken::new(),
+            buffer: vec![0; nx * ny * 4],
+        }
+    }
+
+    /// Takes the pending samples of every pixel with `f`, Which gets the position of the
+    /// sample on the screen and returns its color. The average is gamma 2 corrected
+    pub fn sample(&mut self, f: impl FnMut(f64, f64, &mut dyn Sampler) -> Vec3) {
+        self.sample_with(f, f64::sqrt);
+    }
+
+    /// Same as `sample` but without gamma correction
+    pub fn sample_linear(&mut self, f: impl FnMut(f64, f64, &mut dyn Sampler) -> Vec3) {
+        self.sample_with(f, |c| c);
+    }
+
+    fn sample_with(
+        &mut self,
+        mut f: impl FnMut(f64, f64, &mut dyn Sampler) -> Vec3,
+        encode: fn(f64) -> f64,
+    ) {
+        let mut sampler = self.sampler.create(self.samples as usize, self.seed);
+        let mut index = 0;
+
+        for j in self.start_y..self.start_y + self.ny {
+            for i in self.start_x..self.start_x + self.nx {
+                let pixel = &mut self.pixels[index];
+                let pending = std::mem::replace(&mut self.pending[index], 0);
+
+                // Later rounds carry on from the samples the pixel already took
+                for s in pixel.samples..pixel.samples + pending {
+                    if self.cancel.is_cancelled() {
+                        return;
+                    }
+                    sampler.start_pixel_sample(i, j, s as usize);
+                    let (du, dv) = sampler.get_2d();
+                    let u = (i as f64 + du) / self.x as f64;
+                    let v = (j as f64 + dv) / self.y as f64;
+
+                    pixel.add(f(u, v, sampler.as_mut()));
                 }
+
+                if pending > 0 {
+                    let color = pixel.mean();
+                    let offset = index * 4;
+                    self.buffer[offset] = (255.99 * encode(color.r())) as u8;
+                    self.buffer[offset + 1] = (255.99 * encode(color.g())) as u8;
+                    self.buffer[offset + 2] = (255.99 * encode(color.b())) as u8;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    /// The pixels of the chunk, Flipped so the top row comes first like the frame buffer
+    fn tile(&self) -> Tile {
+        Tile {
+            x: self.start_x,
+            y: self.y - self.start_y - self.ny,
+            width: self.nx,
+            pixels: self
+                .buffer
+                .chunks(self.nx * 4)
+                .rev()
+                .flatten()
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Copies `from`, Which has `channels` values for every pixel of the chunk, Into its
+    /// place in `buf`. Rows are flipped since chunks count them from the bottom
+    fn copy_to<T: Copy>(&self, buf: &mut [T], from: &[T], channels: usize) {
+        let (nx, x) = (self.nx * channels, self.x * channels);
+        for (row, j) in (self.start_y..self.start_y + self.ny).enumerate() {
+            let real_offset = (self.y - j - 1) * x + self.start_x * channels;
+            buf[real_offset..real_offset + nx].copy_from_slice(&from[row * nx..(row + 1) * nx]);
+        }
+    }
+}
+
+/// A render in progress. Keeps the samples every pixel took so far, So more can be added
+/// to them a round or a pass at a time
+pub struct Render<'a, D: Demo + ?Sized> {
+    demo: &'a D,
+    width: usize,
+    height: usize,
+    world: Option<HitableList>,
+    camera: Option<Camera>,
+    chunks: Vec<Chunk>,
+    budget: Budget,
+    passes: u32,
+    /// chunks are sent here as soon as they finish
+    updates: Option<Sender<Update>>,
+    cancel: CancellationToken,
+}
+
+impl<'a, D: Demo + ?Sized> Render<'a, D> {
+    pub fn new(demo: &'a D, width: usize, height: usize, options: RenderOptions) -> Self {
+        let nx = width / VERTICAL_PARTITION;
+        let ny = height / HORIZONTAL_PARTITION;
+        let chunks: Vec<Chunk> = (0..VERTICAL_PARTITION)
+            .flat_map(|j| {
+                (0..HORIZONTAL_PARTITION).map(move |i| Chunk::new(width, height, i, j, &options))
             })
+            .collect();
+
+        Self {
+            demo,
+            width,
+            height,
+            world: demo.world(),
+            camera: demo.camera(nx as f64 / ny as f64),
+            budget: Budget::new(&options, nx * ny * chunks.len()),
+            chunks,
+            passes: 0,
+            updates: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn send_updates(mut self, updates: Sender<Update>) -> Self {
+        self.updates = Some(updates);
+        self
+    }
+
+    /// Stops rendering once `cancel` is cancelled, Leaving the image half done
+    pub fn cancel_with(mut self, cancel: CancellationToken) -> Self {
+        for chunk in &mut self.chunks {
+            chunk.cancel = cancel.clone();
+        }
+        self.cancel = cancel;
+        self
+    }
+
+    /// Takes all of the samples, Handing them out in rounds when sampling adaptively
+    pub fn finish(&mut self) {
+        self.budget.first_round(&mut self.chunks);
+        loop {
+            self.render_chunks();
+            if self.cancel.is_cancelled() || !self.budget.next_round(&mut self.chunks) {
+                break;
+            }
+        }
+    }
+
+    /// Gives every pixel that isn't done yet one more sample. false once every pixel has
+    /// all of its samples, Or has converged when sampling adaptively, Or the render was
+    /// cancelled
+    pub fn pass(&mut self) -> bool {
+        if self.cancel.is_cancelled() {
+            return false;
+        }
+        if self.passes == 0 {
+            self.budget.first_pass(&mut self.chunks);
+        } else if !self.budget.next_pass(&mut self.chunks) {
+            return false;
+        }
+        self.render_chunks();
+        self.passes += 1;
+        !self.cancel.is_cancelled()
+    }
+
+    fn render_chunks(&mut self) {
+        let (demo, camera, world) = (self.demo, self.camera.as_ref(), self.world.as_ref());
+        let (updates, cancel) = (self.updates.as_ref(), &self.cancel);
+        self.chunks.par_iter_mut().for_each(|chunk| {
+            if cancel.is_cancelled() {
+                return;
+            }
+            demo.render_chunk(chunk, camera, world);
+            if cancel.is_cancelled() {
+                return;
+            }
+            if let Some(updates) = updates {
+                updates.send(Update::Tile(chunk.tile())).ok();
+            }
         });
     }
 
+    /// Writes the image so far into `buf`
+    pub fn write(&self, buf: &mut [u8]) {
+        for chunk in &self.chunks {
+            chunk.copy_to(buf, &chunk.buffer, 4);
+        }
+        self.demo.post_process(
+            buf,
+            self.width,
+            self.height,
+            self.camera.as_ref(),
+            self.world.as_ref(),
+        );
+    }
+
+    /// How many samples each pixel took so far, Laid out like the frame buffer
+    pub fn sample_counts(&self) -> Vec<u32> {
+        let mut sample_counts = vec![0; self.width * self.height];
+        for chunk in &self.chunks {
+            let counts: Vec<u32> = chunk.pixels.iter().map(|p| p.samples).collect();
+            chunk.copy_to(&mut sample_counts, &counts, 1);
+        }
+        sample_counts
+    }
+}
+
+pub trait Demo: std::marker::Sync {
+    /// Runs over the finished image, For effects that need to look at neighbouring pixels
+    fn post_process(
+        &self,
+        _buf: &mut [u8],
+        _width: usize,
+        _height: usize,
+        _camera: Option<&Camera>,
+        _world: Option<&HitableList>,
+    ) {
+    }
+
     fn world(&self) -> Option<HitableList> {
         None
     }
@@ -107,13 +359,7 @@ pub trait Demo: std::marker::Sync {
         ))
     }
 
-    fn render_chunk(
-        &self,
-        chunk: &mut Chunk,
-        camera: Option<&Camera>,
-        world: Option<&HitableList>,
-        samples: u8,
-    );
+    fn render_chunk(&self, chunk: &mut Chunk, camera: Option<&Camera>, world: Option<&HitableList>);
 
     fn name(&self) -> &'static str;
 
@@ -134,4 +380,35 @@ pub trait Demo: std::marker::Sync {
             }
         }
     }
+
+    /// Saves how many samples every pixel took as a heat map, Black for the fewest
+    /// through red and yellow to white for the most
+    fn save_sample_map(&self, sample_counts: &[u32], width: usize, height: usize) {
+        let header = format!("P3\n{} {}\n255\n", width, height);
+        let name = format!("{}-samples-{}x{}.ppm", self.name(), width, height);
+
+        let mut file = match File::create(&name) {
+            Ok(file) => file,
+            Err(e) => panic!("couldn't create {}: {}", name, e),
+        };
+        file.write_all(header.as_bytes())
+            .expect("error in writing file header");
+
+        let min = sample_counts.iter().copied().min().unwrap_or(0);
+        let max = sample_counts.iter().copied().max().unwrap_or(0);
+        for &count in sample_counts {
+            let t = if max > min {
+                (count - min) as f64 / (max - min) as f64
+            } else {
+                0.0
+            };
+            let r = (255.99 * (t * 3.0).min(1.0)) as u8;
+            let g = (255.99 * (t * 3.0 - 1.0).clamp(0.0, 1.0)) as u8;
+            let b = (255.99 * (t * 3.0 - 2.0).clamp(0.0, 1.0)) as u8;
+            match file.write_all(format!("{} {} {}\n", r, g, b).as_bytes()) {
+                Ok(_) => (),
+                Err(e) => panic!("couldn't write to {}: {}", name, e),
+            }
+        }
+    }
 }
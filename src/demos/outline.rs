@@ -0,0 +1,134 @@
+use {
+    crate::{
+        types::{sampler::IndependentSampler, HitableList, Sampler, Vec3},
+        Camera,
+    },
+    rayon::prelude::*,
+};
+
+/// Image space outlines for the non photorealistic demos.
+/// Lines are drawn where the first hit through neighbouring pixels lands on a different object,
+/// at a very different depth or on a surface facing a very different direction.
+pub struct Outline {
+    color: Vec3,
+    /// width of the lines in pixels
+    width: f64,
+    /// relative change in depth between neighbours that counts as an edge
+    depth_threshold: f64,
+    /// change in the angle of the normal between neighbours that counts as an edge, in degrees
+    normal_threshold: f64,
+}
+
+/// What the ray through the center of a pixel hit first
+#[derive(Clone, Copy)]
+struct FirstHit {
+    object: usize,
+    depth: f64,
+    normal: Vec3,
+}
+
+impl Outline {
+    pub fn new(color: Vec3, width: f64) -> Self {
+        Self {
+            color,
+            width,
+            depth_threshold: 0.1,
+            normal_threshold: 40.0,
+        }
+    }
+
+    pub fn depth_threshold(mut self, depth_threshold: f64) -> Self {
+        self.depth_threshold = depth_threshold;
+        self
+    }
+    pub fn normal_threshold(mut self, normal_threshold: f64) -> Self {
+        self.normal_threshold = normal_threshold;
+        self
+    }
+
+    /// Draws the outlines over `buf`, Which is a RGBA buffer laid out like the frame buffer
+    pub fn draw(
+        &self,
+        buf: &mut [u8],
+        width: usize,
+        height: usize,
+        camera: &Camera,
+        world: &HitableList,
+    ) {
+        // Rows are stored top to bottom, Same as the frame buffer
+        let hits: Vec<Option<FirstHit>> = (0..width * height)
+            .into_par_iter()
+            .map(|offset| {
+                let (i, j) = (offset % width, height - offset / width - 1);
+                let u = (i as f64 + 0.5) / width as f64;
+                let v = (j as f64 + 0.5) / height as f64;
+                // Same lens sample and hits through transparent surfaces every time
+                let mut sampler = IndependentSampler::new(0);
+                sampler.start_pixel_sample(i, j, 0);
+                let ray = camera.get_ray(u, v, &mut sampler);
+
+                world
+                    .hit_object(&ray, 0.001, f64::MAX)
+                    .map(|(object, hit_rec)| FirstHit {
+                        object,
+                        depth: hit_rec.t * ray.direction().length(),
+                        normal: hit_rec.normal,
+                    })
+            })
+            .collect();
+
+        let mut edges = vec![false; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let offset = y * width + x;
+                let right = x + 1 < width && self.is_edge(hits[offset], hits[offset + 1]);
+                let below = y + 1 < height && self.is_edge(hits[offset], hits[offset + width]);
+                if right || below {
+                    edges[offset] = true;
+                }
+            }
+        }
+
+        // Grow the edges into lines of the requested width
+        let radius = self.width / 2.0;
+        let reach = radius.floor() as isize;
+        let color = [
+            (255.99 * self.color.r().sqrt()) as u8,
+            (255.99 * self.color.g().sqrt()) as u8,
+            (255.99 * self.color.b().sqrt()) as u8,
+        ];
+        buf.par_chunks_mut(width * 4)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for x in 0..width {
+                    let covered = (-reach..=reach).any(|dy| {
+                        (-reach..=reach).any(|dx| {
+                            let (nx, ny) = (x as isize + dx, y as isize + dy);
+                            nx >= 0
+                                && ny >= 0
+                                && (nx as usize) < width
+                                && (ny as usize) < height
+                                && ((dx * dx + dy * dy) as f64) <= radius * radius
+                                && edges[ny as usize * width + nx as usize]
+                        })
+                    });
+                    if covered {
+                        row[x * 4..x * 4 + 3].copy_from_slice(&color);
+                    }
+                }
+            });
+    }
+
+    fn is_edge(&self, a: Option<FirstHit>, b: Option<FirstHit>) -> bool {
+        match (a, b) {
+            (None, None) => false,
+            (Some(a), Some(b)) => {
+                a.object != b.object
+                    || (a.depth - b.depth).abs() > self.depth_threshold * a.depth.min(b.depth)
+                    || a.normal.dot(&b.normal) < self.normal_threshold.to_radians().cos()
+            }
+            // silhouette against the background
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{material::Principled, Hitable, HitableList, Ray, Sampler, Sphere, Vec3},
+    Camera,
+};
+
+pub struct PrincipledBsdf;
+
+impl Demo for PrincipledBsdf {
+    fn name(&self) -> &'static str {
+        "principled-bsdf"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Principled::new(Vec3::new(0.5, 0.5, 0.5)).roughness(0.9)),
+                )),
+                // waxy, subsurface with a bit of sheen
+                Box::new(Sphere::with_material(
+                    Vec3::new(-2.0, -0.1, -1.5),
+                    0.4,
+                    Box::new(
+                        Principled::new(Vec3::new(0.8, 0.2, 0.2))
+                            .roughness(0.6)
+                            .subsurface(0.8)
+                            .sheen(1.0)
+                            .sheen_tint(0.5),
+                    ),
+                )),
+                // car paint
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.0, -0.1, -1.5),
+                    0.4,
+                    Box::new(
+                        Principled::new(Vec3::new(0.05, 0.1, 0.6))
+                            .roughness(0.4)
+                            .clearcoat(1.0)
+                            .clearcoat_gloss(0.9),
+                    ),
+                )),
+                // brushed gold
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -0.1, -1.5),
+                    0.4,
+                    Box::new(
+                        Principled::new(Vec3::new(1.0, 0.78, 0.34))
+                            .metallic(1.0)
+                            .roughness(0.25),
+                    ),
+                )),
+                // glass
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.0, -0.1, -1.5),
+                    0.4,
+                    Box::new(
+                        Principled::new(Vec3::new(0.9, 1.0, 0.95))
+                            .transmission(1.0)
+                            .roughness(0.05)
+                            .ior(1.45),
+                    ),
+                )),
+                // glossy plastic with tinted highlights
+                Box::new(Sphere::with_material(
+                    Vec3::new(2.0, -0.1, -1.5),
+                    0.4,
+                    Box::new(
+                        Principled::new(Vec3::new(0.1, 0.5, 0.1))
+                            .roughness(0.3)
+                            .specular(0.8)
+                            .specular_tint(0.6),
+                    ),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.6, 1.2);
+        let lookat = Vec3::new(0.0, -0.1, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            55.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
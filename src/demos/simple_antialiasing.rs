@@ -1,10 +1,7 @@
-use {
-    crate::{
-        demos::{Chunk, Demo},
-        types::{Hitable, HitableList, Ray, Sphere, Vec3},
-        Camera,
-    },
-    rand::Rng,
+use crate::{
+    demos::{Chunk, Demo},
+    types::{Hitable, HitableList, Ray, Sphere, Vec3},
+    Camera,
 };
 pub struct SimpleAntialiasing;
 
@@ -27,39 +24,13 @@ impl Demo for SimpleAntialiasing {
         chunk: &mut Chunk,
         camera: Option<&Camera>,
         world: Option<&HitableList>,
-        samples: u8,
     ) {
-        let &mut Chunk {
-            x,
-            y,
-            nx,
-            ny,
-            start_x,
-            start_y,
-            ref mut buffer,
-        } = chunk;
         let camera = camera.unwrap();
 
-        let mut rng = rand::thread_rng();
-        let mut offset = 0;
-
-        for j in start_y..start_y + ny {
-            for i in start_x..start_x + nx {
-                let mut color = Vec3::new(0.0, 0.0, 0.0);
-                for _s in 0..samples {
-                    let u = (i as f64 + rng.gen::<f64>()) / x as f64;
-                    let v = (j as f64 + rng.gen::<f64>()) / y as f64;
-
-                    let r = camera.get_ray(u, v);
-                    color += calc_color(r, world.unwrap());
-                }
-                color /= samples as f64;
-                buffer[offset] = (255.99 * color.r()) as u8;
-                buffer[offset + 1] = (255.99 * color.g()) as u8;
-                buffer[offset + 2] = (255.99 * color.b()) as u8;
-                offset += 4;
-            }
-        }
+        chunk.sample_linear(|u, v, sampler| {
+            let r = camera.get_ray(u, v, sampler);
+            calc_color(r, world.unwrap())
+        });
     }
 }
 
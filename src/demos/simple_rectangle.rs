@@ -16,7 +16,6 @@ impl Demo for SimpleRectangle {
         chunk: &mut Chunk,
         _camera: Option<&Camera>,
         _world: Option<&HitableList>,
-        _samples: u8,
     ) {
         let &mut Chunk {
             x,
@@ -26,6 +25,7 @@ impl Demo for SimpleRectangle {
             start_x,
             start_y,
             ref mut buffer,
+            ..
         } = chunk;
         let mut offset = 0;
 
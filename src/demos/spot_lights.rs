@@ -0,0 +1,164 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        light::{IesProfile, PointLight, SpotLight},
+        material::{Conductor, DiffuseLight, Lambertian, OrenNayar, Principled},
+        spectrum, Hitable, HitableList, Light, Ray, Rectangle, Sampler, Sphere, Vec3,
+    },
+    Camera,
+};
+
+pub struct SpotLights;
+
+impl SpotLights {
+    fn lights(&self) -> Vec<Box<dyn Light>> {
+        vec![
+            Box::new(SpotLight::new(
+                Vec3::new(-1.6, 1.5, 0.0),
+                Vec3::new(0.5, -1.5, -1.3),
+                spectrum::blackbody(2700.0) * 6.0,
+                20.0,
+                5.0,
+            )),
+            // hard edged theatre spot
+            Box::new(SpotLight::new(
+                Vec3::new(2.0, 1.0, 0.5),
+                Vec3::new(-1.0, -0.8, -2.2),
+                Vec3::new(0.2, 0.6, 1.0) * 5.0,
+                12.0,
+                0.5,
+            )),
+            Box::new(PointLight::fixture(
+                Vec3::new(0.0, 1.8, -1.6),
+                Vec3::new(0.0, -1.0, 0.0),
+                downlight(),
+                3000.0,
+            )),
+            // faint fill light so the shadows aren't completely black
+            Box::new(PointLight::new(
+                Vec3::new(0.0, 2.0, 3.0),
+                Vec3::new(0.3, 0.3, 0.35),
+            )),
+        ]
+    }
+}
+
+impl Demo for SpotLights {
+    fn name(&self) -> &'static str {
+        "spot-lights"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let white = || Box::new(Lambertian::new(Vec3::new(0.8, 0.8, 0.8)));
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    white(),
+                )),
+                Box::new(Rectangle::with_material(
+                    Vec3::new(-3.0, -0.5, -2.0),
+                    Vec3::new(6.0, 0.0, 0.0),
+                    Vec3::new(0.0, 3.0, 0.0),
+                    white(),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.1, 0.0, -1.3),
+                    0.5,
+                    Box::new(Principled::new(Vec3::new(0.8, 0.2, 0.2)).roughness(0.3)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -0.2, -1.3),
+                    0.3,
+                    Box::new(Conductor::gold(0.3)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.1, 0.0, -1.3),
+                    0.5,
+                    Box::new(OrenNayar::new(Vec3::new(0.3, 0.5, 0.8), 30.0)),
+                )),
+                // Glowing panel shaped by the same profile, Pointing at the wall
+                Box::new(Rectangle::with_material(
+                    Vec3::new(-0.8, 1.3, -0.2),
+                    Vec3::new(1.6, 0.0, 0.0),
+                    Vec3::new(0.0, 0.0, -0.4),
+                    Box::new(
+                        DiffuseLight::blackbody(4000.0, 0.8)
+                            .profile(downlight(), Vec3::new(0.0, -0.3, -1.0)),
+                    ),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.5, 1.5);
+        let lookat = Vec3::new(0.0, -0.1, -1.2);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+        let lights = self.lights();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, &lights, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(
+    ray: Ray,
+    world: &HitableList,
+    lights: &[Box<dyn Light>],
+    depth: u32,
+    sampler: &mut dyn Sampler,
+) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let mut color = material.emitted(&ray, &hit_rec);
+
+        // Rays never hit point and spot lights so they are sampled directly from every hit
+        for light in lights {
+            let sample = light.sample(hit_rec.p);
+            if let Some(reflected) = material.eval(&ray, &hit_rec, sample.direction) {
+                let shadow_ray = Ray::new(hit_rec.p, sample.direction);
+                if world.hit(&shadow_ray, 0.001, sample.distance).is_none() {
+                    color += reflected * sample.radiance;
+                }
+            }
+        }
+
+        if depth < 50 {
+            if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler) {
+                color += calc_color(scattered_ray, world, lights, depth + 1, sampler) * attenuation;
+            }
+        }
+        color
+    } else {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+}
+
+fn downlight() -> IesProfile {
+    IesProfile::parse(include_str!("../../assets/downlight.ies"))
+        .expect("error in parsing ies file")
+}
@@ -17,7 +17,6 @@ impl Demo for SurfaceNormalSphere {
         chunk: &mut Chunk,
         _camera: Option<&Camera>,
         _world: Option<&HitableList>,
-        _samples: u8,
     ) {
         let &mut Chunk {
             x,
@@ -27,6 +26,7 @@ impl Demo for SurfaceNormalSphere {
             start_x,
             start_y,
             ref mut buffer,
+            ..
         } = chunk;
         // Usually, lower_left_corner should've been -1.0,-1.0,-1.0 and
         // horizontal should've been 2.0,0.0,0.0
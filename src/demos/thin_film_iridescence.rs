@@ -0,0 +1,113 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        material::{Conductor, Dielectric, Lambertian, ThinFilm},
+        texture::GradientTexture,
+        Hitable, HitableList, Ray, Sampler, Sphere, Vec3,
+    },
+    Camera,
+};
+
+pub struct ThinFilmIridescence;
+
+impl Demo for ThinFilmIridescence {
+    fn name(&self) -> &'static str {
+        "thin-film-iridescence"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        // Soap film drains down so bubbles are thinner at the top
+        let bubble =
+            || {
+                Box::new(Dielectric::new(1.0).thin_film(
+                    ThinFilm::new(1.33, 900.0).thickness_texture(Box::new(GradientTexture::new(
+                        Vec3::new(1.0, 1.0, 1.0),
+                        Vec3::new(0.2, 0.2, 0.2),
+                    ))),
+                ))
+            };
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.3, 0.3, 0.35))),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.65, 0.1, -1.5),
+                    0.5,
+                    bubble(),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-0.6, 0.35, -2.2),
+                    0.35,
+                    bubble(),
+                )),
+                // anodised aluminium
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.55, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::aluminium(0.05).thin_film(ThinFilm::new(2.4, 260.0))),
+                )),
+                // tempered copper
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.65, 0.0, -1.5),
+                    0.5,
+                    Box::new(Conductor::copper(0.2).thin_film(ThinFilm::new(2.2, 150.0))),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.6, 1.2);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        if depth >= 50 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            let material = hit_rec.material.as_ref();
+            if let (attenuation, Some(scattered_ray)) =
+                material.unwrap().scatter(&ray, &hit_rec, sampler)
+            {
+                calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+            } else {
+                Vec3::new(0.0, 0.0, 0.0)
+            }
+        }
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    demos::{outline::Outline, Chunk, Demo},
+    types::{material::Toon, Hitable, HitableList, Ray, Sampler, Sphere, Vec3},
+    Camera,
+};
+
+pub struct ToonShading;
+
+impl Demo for ToonShading {
+    fn name(&self) -> &'static str {
+        "toon-shading"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let light = Vec3::new(-1.0, 2.0, 1.5);
+        let toon = |color| {
+            Box::new(
+                Toon::new(color)
+                    .light(light)
+                    .rim(Vec3::new(1.0, 1.0, 0.9), 0.2),
+            )
+        };
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(
+                        Toon::new(Vec3::new(0.6, 0.75, 0.5))
+                            .light(light)
+                            .bands(2)
+                            .shadow(0.6),
+                    ),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(-1.1, 0.0, -1.5),
+                    0.5,
+                    toon(Vec3::new(0.9, 0.3, 0.25)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, 0.0, -1.5),
+                    0.5,
+                    toon(Vec3::new(0.95, 0.8, 0.3)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(1.1, 0.0, -1.5),
+                    0.5,
+                    toon(Vec3::new(0.3, 0.5, 0.9)),
+                )),
+                // overlapping spheres get a line where they meet
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.5, -0.3, -0.8),
+                    0.2,
+                    toon(Vec3::new(0.9, 0.3, 0.25)),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.7, -0.3, -0.8),
+                    0.2,
+                    toon(Vec3::new(0.9, 0.3, 0.25)),
+                )),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.0, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            50.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn post_process(
+        &self,
+        buf: &mut [u8],
+        width: usize,
+        height: usize,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        Outline::new(Vec3::new(0.05, 0.05, 0.1), 3.0)
+            .depth_threshold(0.2)
+            .normal_threshold(60.0)
+            .draw(buf, width, height, camera.unwrap(), world.unwrap());
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(ray: Ray, world: &HitableList, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let emitted = material.emitted(&ray, &hit_rec);
+        if depth >= 50 {
+            emitted
+        } else if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler)
+        {
+            emitted + calc_color(scattered_ray, world, depth + 1, sampler) * attenuation
+        } else {
+            emitted
+        }
+    } else {
+        // flat backdrop
+        Vec3::new(0.85, 0.9, 0.95)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    demos::{Chunk, Demo},
+    types::{
+        light::PointLight, material::Lambertian, texture::Perlin, HeterogeneousMedium, Hitable,
+        HitableList, Light, Ray, Sampler, Sphere, Vec3, VoxelGrid,
+    },
+    Camera,
+};
+
+pub struct Volumes;
+
+impl Volumes {
+    fn lights(&self) -> Vec<Box<dyn Light>> {
+        vec![Box::new(PointLight::new(
+            Vec3::new(20.0, 30.0, 10.0),
+            Vec3::new(1.0, 0.95, 0.85) * 1400.0,
+        ))]
+    }
+}
+
+impl Demo for Volumes {
+    fn name(&self) -> &'static str {
+        "volumes"
+    }
+
+    fn world(&self) -> Option<HitableList> {
+        let noise = Perlin::new(0);
+        let cloud = VoxelGrid::from_fn(64, 32, 64, |p| {
+            // squashed ball with the edges eaten away by noise
+            let d = (p - Vec3::new(0.5, 0.5, 0.5)) * 2.0;
+            let falloff = 1.0 - (d.x() * d.x() + 2.0 * d.y() * d.y() + d.z() * d.z()).sqrt();
+            (falloff + 0.8 * noise.turbulence(p * 4.0, 5) - 0.35) * 2.0
+        });
+        let smoke = VoxelGrid::from_nrrd(include_bytes!("../../assets/smoke.nrrd"))
+            .expect("error in decoding smoke volume");
+
+        Some(HitableList {
+            list: vec![
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Box::new(Lambertian::new(Vec3::new(0.5, 0.5, 0.5))),
+                )),
+                Box::new(Sphere::with_material(
+                    Vec3::new(0.0, 0.0, -1.5),
+                    0.5,
+                    Box::new(Lambertian::new(Vec3::new(0.8, 0.3, 0.3))),
+                )),
+                // cloud floating above the ground
+                Box::new(
+                    HeterogeneousMedium::new(
+                        Box::new(Sphere::new(Vec3::new(-1.1, 0.7, -2.0), 1.3)),
+                        cloud,
+                        Vec3::new(-2.1, 0.3, -3.0),
+                        Vec3::new(-0.1, 1.1, -1.0),
+                    )
+                    .density(25.0),
+                ),
+                // dark smoke plume rising from the ground
+                Box::new(
+                    HeterogeneousMedium::new(
+                        Box::new(Sphere::new(Vec3::new(1.3, 0.2, -1.9), 1.2)),
+                        smoke,
+                        Vec3::new(0.7, -0.5, -2.5),
+                        Vec3::new(1.9, 0.9, -1.3),
+                    )
+                    .density(15.0)
+                    .albedo(Vec3::new(0.4, 0.4, 0.4)),
+                ),
+            ],
+        })
+    }
+
+    fn camera(&self, aspect_ratio: f64) -> Option<Camera> {
+        let lookfrom = Vec3::new(0.0, 0.4, 1.0);
+        let lookat = Vec3::new(0.0, 0.1, -1.5);
+        Some(Camera::new(
+            lookfrom,
+            lookat,
+            Vec3::new(0.0, 1.0, 0.0),
+            60.0,
+            aspect_ratio,
+            0.0,
+            1.0,
+        ))
+    }
+
+    fn render_chunk(
+        &self,
+        chunk: &mut Chunk,
+        camera: Option<&Camera>,
+        world: Option<&HitableList>,
+    ) {
+        let camera = camera.unwrap();
+        let world = world.unwrap();
+        let lights = self.lights();
+
+        chunk.sample(|u, v, sampler| {
+            let ray = camera.get_ray(u, v, sampler);
+            calc_color(ray, world, &lights, 0, sampler)
+        });
+    }
+}
+
+fn calc_color(
+    ray: Ray,
+    world: &HitableList,
+    lights: &[Box<dyn Light>],
+    depth: u32,
+    sampler: &mut dyn Sampler,
+) -> Vec3 {
+    sampler.start_bounce(depth as usize);
+    if let Some(hit_rec) = world.hit(&ray, 0.001, f64::MAX) {
+        let material = hit_rec.material.unwrap();
+        let mut color = material.emitted(&ray, &hit_rec);
+
+        // Shadow rays pass through the media, Losing some of the light on the way
+        for light in lights {
+            let sample = light.sample(hit_rec.p);
+            if let Some(reflected) = material.eval(&ray, &hit_rec, sample.direction) {
+                let shadow_ray = Ray::new(hit_rec.p, sample.direction);
+                let transmittance = world.transmittance(&shadow_ray, 0.001, sample.distance);
+                color += reflected * sample.radiance * transmittance;
+            }
+        }
+
+        if depth < 50 {
+            if let (attenuation, Some(scattered_ray)) = material.scatter(&ray, &hit_rec, sampler) {
+                color += calc_color(scattered_ray, world, lights, depth + 1, sampler) * attenuation;
+            }
+        }
+        color
+    } else {
+        let unit_direction = ray.direction().unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+        Vec3::new(1.0, 1.0, 1.0) * (1.0 - t) + Vec3::new(0.5, 0.7, 1.0) * t
+    }
+}
@@ -7,16 +7,24 @@ mod types;
 pub use camera::Camera;
 
 use {
-    demos::Demo,
+    demos::{BackgroundRender, Demo, RenderOptions, Update},
     sdl2::{
         event::{Event, WindowEvent},
         keyboard::Keycode,
         pixels::PixelFormatEnum,
     },
-    std::time::Instant,
+    std::{
+        sync::mpsc::RecvTimeoutError,
+        time::{Duration, Instant},
+    },
+    types::sampler::SamplerKind,
 };
 
-const NUM_SAMPLES: u8 = 100;
+const NUM_SAMPLES: u32 = 100;
+/// Widest 95% confidence interval of a pixel's brightness that counts as converged
+const ADAPTIVE_THRESHOLD: f64 = 0.01;
+/// Samples per pixel progressive renders stop refining at
+const PROGRESSIVE_SAMPLES: u32 = 1024;
 const VERTICAL_PARTITION: usize = 8;
 const HORIZONTAL_PARTITION: usize = 8;
 
@@ -49,7 +57,23 @@ fn main() -> Result<(), String> {
 
     //println!("{:?} {:?} {:?}", texture.query(), texture.color_mod(), texture.alpha_mod());
 
-    let mut active_demo: &dyn Demo = &demos::SimpleRectangle;
+    let mut active_demo: &'static dyn Demo = &demos::SimpleRectangle;
+    let mut options = RenderOptions {
+        samples: NUM_SAMPLES,
+        sampler: SamplerKind::Sobol,
+        seed: 0,
+        adaptive: Some(ADAPTIVE_THRESHOLD),
+    };
+    // Samples taken by each pixel in the last render
+    let mut sample_counts = vec![0; height * width];
+    // Progressive renders show the image after every sample per pixel and keep
+    // refining it until they reach PROGRESSIVE_SAMPLES
+    let mut progressive = false;
+    // Renders run in the background and send the image over as they go,
+    // So the window keeps responding in the meantime
+    let mut render: Option<BackgroundRender> = None;
+    let mut started = Instant::now();
+    let mut passes = 0;
     // TODO: Should update when window is unfocus since the project window retains
     // data from overlapped window
     // TODO: Maybe consider using condition variable to make loop {} not run at full
@@ -68,6 +92,7 @@ fn main() -> Result<(), String> {
                     match keycode {
                         Some(Keycode::S) => {
                             active_demo.save_as_ppm(&buffer, width, height);
+                            active_demo.save_sample_map(&sample_counts, width, height);
                             should_update = false;
                         }
                         Some(Keycode::Num1) => {
@@ -118,6 +143,106 @@ fn main() -> Result<(), String> {
                             active_demo = &demos::FinalScene;
                             should_update = true;
                         }
+                        Some(Keycode::Q) => {
+                            active_demo = &demos::MicrofacetConductors;
+                            should_update = true;
+                        }
+                        Some(Keycode::W) => {
+                            active_demo = &demos::FrostedGlass;
+                            should_update = true;
+                        }
+                        Some(Keycode::E) => {
+                            active_demo = &demos::PrincipledBsdf;
+                            should_update = true;
+                        }
+                        Some(Keycode::R) => {
+                            active_demo = &demos::RoughDiffuse;
+                            should_update = true;
+                        }
+                        Some(Keycode::T) => {
+                            active_demo = &demos::CoatedMaterials;
+                            should_update = true;
+                        }
+                        Some(Keycode::Y) => {
+                            active_demo = &demos::SubsurfaceScattering;
+                            should_update = true;
+                        }
+                        Some(Keycode::U) => {
+                            active_demo = &demos::ThinFilmIridescence;
+                            should_update = true;
+                        }
+                        Some(Keycode::I) => {
+                            active_demo = &demos::MixedMaterials;
+                            should_update = true;
+                        }
+                        Some(Keycode::O) => {
+                            active_demo = &demos::AlphaCutout;
+                            should_update = true;
+                        }
+                        Some(Keycode::P) => {
+                            active_demo = &demos::NormalMapping;
+                            should_update = true;
+                        }
+                        Some(Keycode::A) => {
+                            active_demo = &demos::AnisotropicMetal;
+                            should_update = true;
+                        }
+                        Some(Keycode::D) => {
+                            active_demo = &demos::VelvetCloth;
+                            should_update = true;
+                        }
+                        Some(Keycode::F) => {
+                            active_demo = &demos::ToonShading;
+                            should_update = true;
+                        }
+                        Some(Keycode::G) => {
+                            active_demo = &demos::BlackbodyLights;
+                            should_update = true;
+                        }
+                        Some(Keycode::H) => {
+                            active_demo = &demos::SpotLights;
+                            should_update = true;
+                        }
+                        Some(Keycode::J) => {
+                            active_demo = &demos::FairyLights;
+                            should_update = true;
+                        }
+                        Some(Keycode::K) => {
+                            active_demo = &demos::Volumes;
+                            should_update = true;
+                        }
+                        Some(Keycode::L) => {
+                            active_demo = &demos::Fire;
+                            should_update = true;
+                        }
+                        Some(Keycode::Z) => {
+                            options.sampler = options.sampler.next();
+                            should_update = true;
+                        }
+                        Some(Keycode::X) => {
+                            options.seed += 1;
+                            should_update = true;
+                        }
+                        Some(Keycode::C) => {
+                            options.adaptive = match options.adaptive {
+                                Some(_) => None,
+                                None => Some(ADAPTIVE_THRESHOLD),
+                            };
+                            should_update = true;
+                        }
+                        Some(Keycode::V) => {
+                            progressive = !progressive;
+                            should_update = true;
+                        }
+                        Some(Keycode::Space) if render.is_some() => {
+                            // Cancels the render, Keeping the image so far
+                            render = None;
+                            println!(
+                                "Demo {} stopped after {} passes",
+                                active_demo.name(),
+                                passes
+                            );
+                        }
                         None => unreachable!(),
                         _ => (),
                     };
@@ -138,18 +263,69 @@ fn main() -> Result<(), String> {
             };
         }
         if should_update {
-            let now = Instant::now();
-            active_demo.render(&mut buffer, width, height, NUM_SAMPLES);
-            println!(
-                "Demo {} Time Taken(s) = {}",
-                active_demo.name(),
-                now.elapsed().as_secs_f64()
-            );
-
-            texture.update(None, &buffer, width * 4).unwrap();
-            canvas.copy(&texture, None, None).unwrap();
-            canvas.present();
+            let samples = if progressive {
+                PROGRESSIVE_SAMPLES
+            } else {
+                NUM_SAMPLES
+            };
+            let options = RenderOptions { samples, ..options };
+            // The render in flight stops taking samples before the new one starts
+            if let Some(render) = render.take() {
+                render.cancel();
+            }
+            render = Some(BackgroundRender::spawn(
+                active_demo,
+                width,
+                height,
+                options,
+                progressive,
+            ));
+            started = Instant::now();
+            passes = 0;
             should_update = false;
         }
+
+        if let Some(background) = &render {
+            // Waits a frame at most for the first update so the loop doesn't spin while
+            // rendering, Then draws whatever else came in
+            let updates = background.updates();
+            let first = updates.recv_timeout(Duration::from_millis(16));
+            let finished = matches!(first, Err(RecvTimeoutError::Disconnected));
+
+            let mut updated = false;
+            for update in first.into_iter().chain(updates.try_iter()) {
+                match update {
+                    Update::Tile(tile) => tile.draw(&mut buffer, width),
+                    Update::Frame {
+                        pixels,
+                        sample_counts: counts,
+                    } => {
+                        buffer = pixels;
+                        sample_counts = counts;
+                        passes += 1;
+                    }
+                }
+                updated = true;
+            }
+
+            if updated {
+                texture.update(None, &buffer, width * 4).unwrap();
+                canvas.copy(&texture, None, None).unwrap();
+                canvas.present();
+            }
+            if finished {
+                println!(
+                    "Demo {} Sampler {} Seed {} Adaptive {} Progressive {} Samples {} Time Taken(s) = {}",
+                    active_demo.name(),
+                    options.sampler.name(),
+                    options.seed,
+                    options.adaptive.is_some(),
+                    progressive,
+                    sample_counts.iter().map(|&s| s as u64).sum::<u64>(),
+                    started.elapsed().as_secs_f64()
+                );
+                render = None;
+            }
+        }
     }
 }
@@ -0,0 +1,239 @@
+use {
+    crate::types::{
+        material::Isotropic, sampler, spectrum, HitRecord, Hitable, Material, Ray, Sampler, Vec3,
+        VoxelGrid,
+    },
+    std::sync::Arc,
+};
+
+/// Participating media like clouds and smoke with a density that varies through space.
+/// The density comes from a voxel grid stretched over the box from `min` to `max`
+/// and the medium fills the inside of `boundary`, Which has to be a closed convex object.
+///
+/// Rays are scattered with delta tracking and shadow rays are attenuated with ratio tracking.
+/// See "Monte Carlo Methods for Volumetric Light Transport Simulation" by Novák et al.
+pub struct HeterogeneousMedium {
+    boundary: Box<dyn Hitable>,
+    grid: VoxelGrid,
+    min: Vec3,
+    max: Vec3,
+    /// extinction coefficient where the grid has a density of 1
+    density: f64,
+    albedo: Vec3,
+    emission: Option<Arc<Emission>>,
+    /// Built from the albedo and the emission
+    phase_function: Box<dyn Material>,
+}
+
+/// Light given off by the medium, Looked up from a grid covering the same box as the density
+enum Emission {
+    /// Grid is the temperature as a fraction of `max_kelvin`
+    Temperature {
+        grid: VoxelGrid,
+        max_kelvin: f64,
+        intensity: f64,
+    },
+    /// Grid scales `color`
+    Color { grid: VoxelGrid, color: Vec3 },
+}
+
+impl Emission {
+    fn radiance(&self, local: Vec3) -> Vec3 {
+        match self {
+            Emission::Temperature {
+                grid,
+                max_kelvin,
+                intensity,
+            } => {
+                let t = grid.density(local).min(1.0);
+                // Nothing visible is given off below the Draper point
+                if t * max_kelvin < 800.0 {
+                    return Vec3::new(0.0, 0.0, 0.0);
+                }
+                // Total power grows with the fourth power of the temperature(Stefan-Boltzmann law)
+                spectrum::blackbody(t * max_kelvin) * (intensity * t.powi(4))
+            }
+            Emission::Color { grid, color } => *color * grid.density(local),
+        }
+    }
+}
+
+/// Isotropic scattering plus the emission of the medium
+struct Phase {
+    isotropic: Isotropic,
+    albedo: Vec3,
+    emission: Arc<Emission>,
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Material for Phase {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        self.isotropic.scatter(ray_in, hit_rec, sampler)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        self.isotropic.eval(ray_in, hit_rec, direction)
+    }
+
+    /// Collision estimator, Each collision adds the emission weighted by the chance
+    /// of the collision being an absorption(sigma_a / sigma_t = 1 - albedo)
+    fn emitted(&self, _ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        let local = (hit_rec.p - self.min) / (self.max - self.min);
+        (Vec3::new(1.0, 1.0, 1.0) - self.albedo) * self.emission.radiance(local)
+    }
+}
+
+impl HeterogeneousMedium {
+    pub fn new(boundary: Box<dyn Hitable>, grid: VoxelGrid, min: Vec3, max: Vec3) -> Self {
+        let albedo = Vec3::new(1.0, 1.0, 1.0);
+        Self {
+            boundary,
+            grid,
+            min,
+            max,
+            density: 1.0,
+            albedo,
+            emission: None,
+            phase_function: Box::new(Isotropic::new(albedo)),
+        }
+    }
+
+    /// Scales the densities in the grid, Higher values make the medium thicker
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+    /// Fraction of the light that's scattered instead of absorbed at each collision
+    pub fn albedo(mut self, albedo: Vec3) -> Self {
+        self.albedo = albedo;
+        self.update_phase_function();
+        self
+    }
+
+    /// Makes the medium glow like a blackbody, For fire and explosions.
+    /// `temperature` covers the same box as the density and holds the temperature
+    /// as a fraction of `max_kelvin`. `intensity` is the brightness at `max_kelvin`.
+    ///
+    /// Light is only given off where the medium absorbs light, So the density has to
+    /// be non zero and the albedo below 1 wherever the medium should glow
+    pub fn temperature(mut self, temperature: VoxelGrid, max_kelvin: f64, intensity: f64) -> Self {
+        self.emission = Some(Arc::new(Emission::Temperature {
+            grid: temperature,
+            max_kelvin,
+            intensity,
+        }));
+        self.update_phase_function();
+        self
+    }
+
+    /// Same as `temperature` but with the color given directly, Scaled by the `emission` grid
+    pub fn emission(mut self, emission: VoxelGrid, color: Vec3) -> Self {
+        self.emission = Some(Arc::new(Emission::Color {
+            grid: emission,
+            color,
+        }));
+        self.update_phase_function();
+        self
+    }
+
+    fn update_phase_function(&mut self) {
+        self.phase_function = match &self.emission {
+            Some(emission) => Box::new(Phase {
+                isotropic: Isotropic::new(self.albedo),
+                albedo: self.albedo,
+                emission: emission.clone(),
+                min: self.min,
+                max: self.max,
+            }),
+            None => Box::new(Isotropic::new(self.albedo)),
+        };
+    }
+
+    fn extinction(&self, p: Vec3) -> f64 {
+        let local = (p - self.min) / (self.max - self.min);
+        self.density * self.grid.density(local)
+    }
+
+    /// Part of the ray between `t_min` and `t_max` that's inside the boundary
+    fn interval(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<(f64, f64)> {
+        let entry = self.boundary.hit(ray, f64::MIN, f64::MAX)?;
+        let exit = self.boundary.hit(ray, entry.t + 0.0001, f64::MAX)?;
+
+        let t1 = entry.t.max(t_min).max(0.0);
+        let t2 = exit.t.min(t_max);
+        if t1 >= t2 {
+            None
+        } else {
+            Some((t1, t2))
+        }
+    }
+
+    /// Distances to tentative collisions along the ray, Sampled as if the medium had the
+    /// maximum density everywhere. Real collisions are picked from these by the caller
+    fn tentative_collisions<'a>(
+        &'a self,
+        ray: &'a Ray,
+        t1: f64,
+        t2: f64,
+    ) -> impl Iterator<Item = (f64, f64)> + 'a {
+        let majorant = self.density * self.grid.max_density();
+        let speed = ray.direction().length();
+        let mut t = t1;
+
+        std::iter::from_fn(move || {
+            if majorant <= 0.0 {
+                return None;
+            }
+            t -= (1.0 - sampler::random()).ln() / (majorant * speed);
+            if t >= t2 {
+                None
+            } else {
+                // ratio of the real density to the majorant at the collision
+                let p = ray.point_at_parameter(t);
+                Some((t, self.extinction(p) / majorant))
+            }
+        })
+    }
+}
+
+impl Hitable for HeterogeneousMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (t1, t2) = self.interval(ray, t_min, t_max)?;
+        // Delta tracking, Each tentative collision is real with probability
+        // equal to the ratio of the density to the majorant
+        let (t, _) = self
+            .tentative_collisions(ray, t1, t2)
+            .find(|&(_, ratio)| sampler::random() < ratio)?;
+
+        Some(HitRecord {
+            t,
+            p: ray.point_at_parameter(t),
+            // Media have no surface so these are arbitrary
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            tangent: Vec3::new(0.0, 1.0, 0.0),
+            u: 0.0,
+            v: 0.0,
+            dpdu: Vec3::new(0.0, 0.0, 0.0),
+            dpdv: Vec3::new(0.0, 0.0, 0.0),
+            material: Some(&self.phase_function),
+        })
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        match self.interval(ray, t_min, t_max) {
+            // Ratio tracking, Weigh by the chance of passing through every tentative collision
+            Some((t1, t2)) => self
+                .tentative_collisions(ray, t1, t2)
+                .fold(1.0, |transmittance, (_, ratio)| {
+                    transmittance * (1.0 - ratio)
+                }),
+            None => 1.0,
+        }
+    }
+}
@@ -17,6 +17,19 @@ pub struct HitRecord<'a> {
     /// unit outward facing normal
     pub normal: Vec3,
 
+    /// unit vector perpendicular to the normal, pointing in the direction u increases.
+    /// Together with the normal, It forms the tangent frame used by normal maps
+    pub tangent: Vec3,
+
+    /// surface coordinates of p, both in 0..1 range. Used to look up textures
+    pub u: f64,
+    pub v: f64,
+
+    /// how far p moves on the surface for a change of 1 in u and in v.
+    /// Zero for things without a surface
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
+
     /// material if any of the surface
     pub material: Option<&'a Box<dyn Material>>,
 }
@@ -25,4 +38,23 @@ pub trait Hitable: Send + Sync {
     fn hit(&self, _ray: &Ray, _t_min: f64, _t_max: f64) -> Option<HitRecord> {
         None
     }
+
+    /// Fraction of the light that makes it through this object between `t_min` and `t_max`.
+    /// Used for shadow rays, Partially transparent surfaces let some of the light through
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let mut transmittance = 1.0;
+        let mut start = t_min;
+        while let Some(hit_rec) = self.hit(ray, start, t_max) {
+            let opacity = match hit_rec.material {
+                Some(material) => material.opacity(&hit_rec),
+                None => 1.0,
+            };
+            transmittance *= 1.0 - opacity.clamp(0.0, 1.0);
+            if transmittance <= 0.0 {
+                return 0.0;
+            }
+            start = hit_rec.t;
+        }
+        transmittance
+    }
 }
@@ -1,4 +1,4 @@
-use crate::types::{HitRecord, Hitable, Ray};
+use crate::types::{sampler, HitRecord, Hitable, Ray};
 
 pub struct HitableList {
     pub list: Vec<Box<dyn Hitable>>,
@@ -6,20 +6,53 @@ pub struct HitableList {
 
 impl Hitable for HitableList {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
-        let mut closest_so_far = t_max;
-        let mut hit_rec: Option<HitRecord> = None;
+        self.hit_object(ray, t_min, t_max)
+            .map(|(_, hit_rec)| hit_rec)
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let mut transmittance = 1.0;
         for obj in &self.list {
-            if let Some(l_hit_rec) = obj.hit(ray, t_min, closest_so_far) {
-                closest_so_far = l_hit_rec.t;
-                hit_rec = Some(l_hit_rec);
+            transmittance *= obj.transmittance(ray, t_min, t_max);
+            if transmittance <= 0.0 {
+                return 0.0;
             }
         }
-        hit_rec
+        transmittance
     }
 }
 
 impl HitableList {
+    /// Same as `hit` but also returns the index of the object that was hit in `list`
+    pub fn hit_object(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<(usize, HitRecord<'_>)> {
+        let mut closest_so_far = t_max;
+        let mut hit_rec: Option<(usize, HitRecord)> = None;
+        for (index, obj) in self.list.iter().enumerate() {
+            // Keep looking further along the ray if we hit a transparent part of the object
+            let mut start = t_min;
+            while let Some(l_hit_rec) = obj.hit(ray, start, closest_so_far) {
+                if is_opaque(&l_hit_rec) {
+                    closest_so_far = l_hit_rec.t;
+                    hit_rec = Some((index, l_hit_rec));
+                    break;
+                }
+                start = l_hit_rec.t;
+            }
+        }
+        hit_rec
+    }
+
     pub fn push(&mut self, obj: Box<dyn Hitable>) {
         self.list.push(obj);
     }
 }
+
+// Partially transparent surfaces are hit with probability equal to their opacity
+fn is_opaque(hit_rec: &HitRecord) -> bool {
+    let opacity = match hit_rec.material {
+        Some(material) => material.opacity(hit_rec),
+        None => 1.0,
+    };
+
+    opacity >= 1.0 || (opacity > 0.0 && sampler::random() < opacity)
+}
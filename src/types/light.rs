@@ -0,0 +1,245 @@
+mod bounds;
+mod bvh;
+mod ies;
+
+pub use bounds::LightBounds;
+pub use bvh::LightBvh;
+pub use ies::IesProfile;
+
+use {
+    crate::types::{
+        sampler,
+        spectrum::{self, LUMENS_PER_WATT},
+        Onb, Vec3,
+    },
+    std::f64::consts::PI,
+};
+
+/// Lights that are sampled directly from a surface.
+/// Point and spot lights can't be hit by rays so this is the only way they contribute
+pub trait Light: Send + Sync {
+    /// Light arriving at `p` from this light, Ignoring anything that might be in the way
+    fn sample(&self, p: Vec3) -> LightSample;
+
+    /// Where the light is, Which way it's facing and how bright it is
+    fn bounds(&self) -> LightBounds;
+}
+
+pub struct LightSample {
+    /// unit vector from the shading point towards the light
+    pub direction: Vec3,
+    /// distance to the light. Shadow rays shouldn't look for hits further than this
+    pub distance: f64,
+    /// light arriving at the shading point, Already includes the falloff with distance
+    /// and is divided by the probability of picking this sample
+    pub radiance: Vec3,
+}
+
+/// Fixture profile with the frame it's oriented in
+pub(crate) struct Profile {
+    ies: IesProfile,
+    frame: Onb,
+}
+
+impl Profile {
+    /// `axis` is the direction the fixture is pointing in
+    pub(crate) fn new(ies: IesProfile, axis: Vec3) -> Self {
+        Self {
+            ies,
+            frame: Onb::from_w(axis.unit_vector()),
+        }
+    }
+
+    /// Intensity towards world space `direction` relative to the brightest direction
+    pub(crate) fn scale(&self, direction: Vec3) -> f64 {
+        self.ies.relative_intensity(self.frame.to_local(direction))
+    }
+}
+
+/// Light given off from a single point, Equally in all directions unless it has a profile
+pub struct PointLight {
+    position: Vec3,
+    intensity: Vec3,
+    profile: Option<Profile>,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, intensity: Vec3) -> Self {
+        Self {
+            position,
+            intensity,
+            profile: None,
+        }
+    }
+
+    /// A real fixture with `kelvin` color temperature. The brightness comes from the profile
+    /// and `axis` is the direction it's pointing in(the nadir of the profile)
+    pub fn fixture(position: Vec3, axis: Vec3, profile: IesProfile, kelvin: f64) -> Self {
+        let intensity = spectrum::blackbody(kelvin) * (profile.max_candela() / LUMENS_PER_WATT);
+        Self::new(position, intensity).profile(profile, axis)
+    }
+
+    /// Modulates the intensity by the direction using a photometric profile.
+    /// `intensity` is what the light gives off in the brightest direction of the profile
+    pub fn profile(mut self, profile: IesProfile, axis: Vec3) -> Self {
+        self.profile = Some(Profile::new(profile, axis));
+        self
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: Vec3) -> LightSample {
+        let to_light = self.position - p;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+        let scale = match &self.profile {
+            Some(profile) => profile.scale(-direction),
+            None => 1.0,
+        };
+
+        LightSample {
+            direction,
+            distance,
+            radiance: self.intensity * (scale / (distance * distance)),
+        }
+    }
+
+    fn bounds(&self) -> LightBounds {
+        let power = 4.0 * PI * spectrum::luminance(self.intensity);
+        LightBounds::omnidirectional(self.position, self.position, power)
+    }
+}
+
+/// Point light that only shines inside a cone
+pub struct SpotLight {
+    position: Vec3,
+    /// unit vector the spot light is pointing in
+    direction: Vec3,
+    intensity: Vec3,
+    /// cosines of the angles where the falloff starts and where the light is completely off
+    cos_falloff_start: f64,
+    cos_cone: f64,
+}
+
+impl SpotLight {
+    /// `cone_angle` is the half angle of the cone in degrees. Intensity falls off smoothly
+    /// over the last `falloff` degrees of the cone
+    pub fn new(
+        position: Vec3,
+        direction: Vec3,
+        intensity: Vec3,
+        cone_angle: f64,
+        falloff: f64,
+    ) -> Self {
+        let falloff = falloff.clamp(0.0, cone_angle);
+        Self {
+            position,
+            direction: direction.unit_vector(),
+            intensity,
+            cos_falloff_start: (cone_angle - falloff).to_radians().cos(),
+            cos_cone: cone_angle.to_radians().cos(),
+        }
+    }
+
+    fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_falloff_start {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_cone {
+            return 0.0;
+        }
+        // smoothstep between the edge of the cone and the start of the falloff
+        let t = (cos_theta - self.cos_cone) / (self.cos_falloff_start - self.cos_cone);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: Vec3) -> LightSample {
+        let to_light = self.position - p;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+        let scale = self.falloff((-direction).dot(&self.direction));
+
+        LightSample {
+            direction,
+            distance,
+            radiance: self.intensity * (scale / (distance * distance)),
+        }
+    }
+
+    fn bounds(&self) -> LightBounds {
+        LightBounds {
+            min: self.position,
+            max: self.position,
+            power: 4.0 * PI * spectrum::luminance(self.intensity),
+            axis: self.direction,
+            cos_theta_o: 1.0,
+            cos_theta_e: self.cos_cone,
+        }
+    }
+}
+
+/// Glowing sphere, The same as a `Sphere` with a `DiffuseLight` material.
+/// Add both to the scene, The sphere so it can be seen and this so it can be sampled
+pub struct SphereLight {
+    center: Vec3,
+    radius: f64,
+    emit: Vec3,
+}
+
+impl SphereLight {
+    pub fn new(center: Vec3, radius: f64, emit: Vec3) -> Self {
+        Self {
+            center,
+            radius,
+            emit,
+        }
+    }
+}
+
+impl Light for SphereLight {
+    fn sample(&self, p: Vec3) -> LightSample {
+        let to_center = self.center - p;
+        let distance2 = to_center.sq_len();
+        let radius2 = self.radius * self.radius;
+        if distance2 <= radius2 {
+            return LightSample {
+                direction: Vec3::new(0.0, 0.0, 1.0),
+                distance: 0.0,
+                radiance: Vec3::new(0.0, 0.0, 0.0),
+            };
+        }
+
+        // Uniformly sample the cone of directions the sphere covers as seen from p
+        let cos_max = (1.0 - radius2 / distance2).sqrt();
+        let cos_theta = 1.0 - sampler::random() * (1.0 - cos_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * sampler::random();
+        let frame = Onb::from_w(to_center / distance2.sqrt());
+        let direction = frame.to_world(Vec3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ));
+
+        // Distance to the near side of the sphere along the sampled direction
+        let b = to_center.dot(&direction);
+        let distance = b - (b * b - distance2 + radius2).max(0.0).sqrt();
+        let pdf = 1.0 / (2.0 * PI * (1.0 - cos_max));
+
+        LightSample {
+            direction,
+            distance,
+            radiance: self.emit / pdf,
+        }
+    }
+
+    fn bounds(&self) -> LightBounds {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        // A lambertian emitter gives off PI * radiance * area
+        let area = 4.0 * PI * self.radius * self.radius;
+        let power = PI * area * spectrum::luminance(self.emit);
+        LightBounds::omnidirectional(self.center - r, self.center + r, power)
+    }
+}
@@ -0,0 +1,159 @@
+use {crate::types::Vec3, std::f64::consts::PI};
+
+/// Conservative bounds on where a group of lights is, Which way it's facing
+/// and how much light it gives off. Used to estimate how much a group of lights
+/// could contribute to a point without looking at every light in it.
+///
+/// See "Importance Sampling of Many Lights with Adaptive Tree Splitting"
+/// by Alejandro Conty Estevez and Christopher Kulla
+#[derive(Clone, Copy)]
+pub struct LightBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+    /// total luminous power
+    pub power: f64,
+    /// unit vector the lights are facing
+    pub axis: Vec3,
+    /// cosine of the angle around `axis` the normals of all the lights are in
+    pub cos_theta_o: f64,
+    /// cosine of the angle from the normals that light is given off in.
+    /// PI / 2 for lambertian emitters
+    pub cos_theta_e: f64,
+}
+
+impl LightBounds {
+    /// Light given off in every direction from inside the box
+    pub fn omnidirectional(min: Vec3, max: Vec3, power: f64) -> Self {
+        Self {
+            min,
+            max,
+            power,
+            axis: Vec3::new(0.0, 0.0, 1.0),
+            cos_theta_o: -1.0,
+            cos_theta_e: 0.0,
+        }
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn union(&self, other: &LightBounds) -> LightBounds {
+        let (axis, cos_theta_o) = union_cones(
+            (self.axis, self.cos_theta_o),
+            (other.axis, other.cos_theta_o),
+        );
+
+        LightBounds {
+            min: Vec3::new(
+                self.min.x().min(other.min.x()),
+                self.min.y().min(other.min.y()),
+                self.min.z().min(other.min.z()),
+            ),
+            max: Vec3::new(
+                self.max.x().max(other.max.x()),
+                self.max.y().max(other.max.y()),
+                self.max.z().max(other.max.z()),
+            ),
+            power: self.power + other.power,
+            axis,
+            cos_theta_o,
+            cos_theta_e: self.cos_theta_e.min(other.cos_theta_e),
+        }
+    }
+
+    /// Estimate of the light from these bounds arriving at `p`.
+    /// Only relative values matter, It's used to pick between groups of lights
+    pub fn importance(&self, p: Vec3) -> f64 {
+        let centroid = self.centroid();
+        let half_diagonal = (self.max - self.min).length() * 0.5;
+        let to_p = p - centroid;
+        // Don't let points close to or inside the bounds blow up the estimate
+        let distance2 = to_p.sq_len().max(half_diagonal * half_diagonal);
+        let distance = to_p.length();
+
+        let cos_theta_w = if distance > 0.0 {
+            self.axis.dot(&(to_p / distance))
+        } else {
+            1.0
+        };
+        let sin_theta_w = (1.0 - cos_theta_w * cos_theta_w).max(0.0).sqrt();
+
+        // Angle the bounds cover as seen from p
+        let cos_theta_b = if distance > half_diagonal {
+            (1.0 - half_diagonal * half_diagonal / (distance * distance))
+                .max(0.0)
+                .sqrt()
+        } else {
+            -1.0
+        };
+        let sin_theta_b = (1.0 - cos_theta_b * cos_theta_b).max(0.0).sqrt();
+
+        // cos(max(0, theta_w - theta_o - theta_b)), The smallest angle between
+        // the direction to p and any of the normals of the lights
+        let cos_theta_p = if cos_theta_w >= self.cos_theta_o {
+            1.0
+        } else {
+            let sin_theta_o = (1.0 - self.cos_theta_o * self.cos_theta_o).max(0.0).sqrt();
+            let cos_theta_x = cos_theta_w * self.cos_theta_o + sin_theta_w * sin_theta_o;
+            let sin_theta_x = sin_theta_w * self.cos_theta_o - cos_theta_w * sin_theta_o;
+            if cos_theta_x >= cos_theta_b {
+                1.0
+            } else {
+                cos_theta_x * cos_theta_b + sin_theta_x * sin_theta_b
+            }
+        };
+        if cos_theta_p <= self.cos_theta_e {
+            return 0.0;
+        }
+
+        self.power * cos_theta_p / distance2
+    }
+
+    /// Cost of a group of lights with these bounds, Used when building the tree.
+    /// Grows with the power, The surface area and how spread out the directions are
+    pub fn cost(&self) -> f64 {
+        let theta_o = self.cos_theta_o.clamp(-1.0, 1.0).acos();
+        let theta_e = self.cos_theta_e.clamp(-1.0, 1.0).acos();
+        let theta_w = (theta_o + theta_e).min(PI);
+        let (sin_theta_o, cos_theta_o) = theta_o.sin_cos();
+        let m_omega = 2.0 * PI * (1.0 - cos_theta_o)
+            + PI / 2.0
+                * (2.0 * theta_w * sin_theta_o
+                    - (theta_o - 2.0 * theta_w).cos()
+                    - 2.0 * theta_o * sin_theta_o
+                    + cos_theta_o);
+
+        let d = self.max - self.min;
+        let area = 2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x());
+        // Lights that are all in one spot still have some cost
+        self.power * m_omega * area.max(1e-6)
+    }
+}
+
+/// Smallest cone containing both the cones. Cones are an axis and the cosine of the half angle
+fn union_cones(a: (Vec3, f64), b: (Vec3, f64)) -> (Vec3, f64) {
+    let theta_a = a.1.clamp(-1.0, 1.0).acos();
+    let theta_b = b.1.clamp(-1.0, 1.0).acos();
+    let theta_d = a.0.dot(&b.0).clamp(-1.0, 1.0).acos();
+
+    if (theta_d + theta_b).min(PI) <= theta_a {
+        return a;
+    }
+    if (theta_d + theta_a).min(PI) <= theta_b {
+        return b;
+    }
+
+    let theta_o = (theta_a + theta_d + theta_b) / 2.0;
+    let rotation_axis = a.0.cross(&b.0);
+    if theta_o >= PI || rotation_axis.sq_len() == 0.0 {
+        return (a.0, -1.0);
+    }
+
+    // Rotate the axis of a towards b by theta_o - theta_a(Rodrigues' rotation formula)
+    let k = rotation_axis.unit_vector();
+    let (sin_r, cos_r) = (theta_o - theta_a).sin_cos();
+    let axis = a.0 * cos_r + k.cross(&a.0) * sin_r + k * (k.dot(&a.0) * (1.0 - cos_r));
+
+    (axis.unit_vector(), theta_o.cos())
+}
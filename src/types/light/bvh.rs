@@ -0,0 +1,219 @@
+use {
+    super::{Light, LightBounds, LightSample},
+    crate::types::{sampler, Vec3},
+};
+
+const BUCKETS: usize = 12;
+
+/// Hierarchy of lights for scenes with lots of them.
+/// Each sample walks down the tree picking a child with probability proportional to
+/// how much light it could contribute to the shading point, So nearby and bright lights
+/// facing the point are sampled a lot more often than the rest.
+///
+/// See "Importance Sampling of Many Lights with Adaptive Tree Splitting"
+/// by Alejandro Conty Estevez and Christopher Kulla
+pub struct LightBvh {
+    lights: Vec<Box<dyn Light>>,
+    /// root is the first node
+    nodes: Vec<Node>,
+}
+
+enum Node {
+    Leaf {
+        bounds: LightBounds,
+        light: usize,
+    },
+    Interior {
+        bounds: LightBounds,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl Node {
+    fn bounds(&self) -> &LightBounds {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Interior { bounds, .. } => bounds,
+        }
+    }
+}
+
+impl LightBvh {
+    pub fn new(lights: Vec<Box<dyn Light>>) -> Self {
+        let mut items: Vec<(usize, LightBounds)> = lights
+            .iter()
+            .map(|light| light.bounds())
+            .enumerate()
+            .filter(|(_, bounds)| bounds.power > 0.0)
+            .collect();
+
+        let mut bvh = Self {
+            lights,
+            nodes: Vec::with_capacity(items.len() * 2),
+        };
+        if !items.is_empty() {
+            bvh.build(&mut items);
+        }
+        bvh
+    }
+
+    /// Builds the tree for `items` and returns the index of it's root node
+    fn build(&mut self, items: &mut [(usize, LightBounds)]) -> usize {
+        let bounds = items[1..]
+            .iter()
+            .fold(items[0].1, |bounds, (_, b)| bounds.union(b));
+        if items.len() == 1 {
+            self.nodes.push(Node::Leaf {
+                bounds,
+                light: items[0].0,
+            });
+            return self.nodes.len() - 1;
+        }
+
+        let mid = split(items, &bounds);
+
+        // Reserve the spot for this node so the root always ends up first
+        let index = self.nodes.len();
+        self.nodes.push(Node::Leaf { bounds, light: 0 });
+        let (left_items, right_items) = items.split_at_mut(mid);
+        let left = self.build(left_items);
+        let right = self.build(right_items);
+        self.nodes[index] = Node::Interior {
+            bounds,
+            left,
+            right,
+        };
+        index
+    }
+}
+
+/// Reorders `items` so the lights before the returned index go into the left child.
+/// Tries splitting along each axis at bucket boundaries and keeps the cheapest split
+fn split(items: &mut [(usize, LightBounds)], bounds: &LightBounds) -> usize {
+    let (min, max) = items.iter().fold(
+        (items[0].1.centroid(), items[0].1.centroid()),
+        |(min, max), (_, b)| {
+            let c = b.centroid();
+            (
+                Vec3::new(min.x().min(c.x()), min.y().min(c.y()), min.z().min(c.z())),
+                Vec3::new(max.x().max(c.x()), max.y().max(c.y()), max.z().max(c.z())),
+            )
+        },
+    );
+    let extent = bounds.max - bounds.min;
+    let max_extent = extent.x().max(extent.y()).max(extent.z());
+
+    let bucket = |b: &LightBounds, axis: usize| {
+        let t = (b.centroid()[axis] - min[axis]) / (max[axis] - min[axis]);
+        ((t * BUCKETS as f64) as usize).min(BUCKETS - 1)
+    };
+
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        if max[axis] <= min[axis] {
+            continue;
+        }
+        let mut buckets: [Option<LightBounds>; BUCKETS] = [None; BUCKETS];
+        for (_, b) in items.iter() {
+            let i = bucket(b, axis);
+            buckets[i] = Some(buckets[i].map_or(*b, |bucket| bucket.union(b)));
+        }
+
+        // Long thin boxes are split across their length
+        let regularization = max_extent / extent[axis].max(1e-9);
+        for split_at in 1..BUCKETS {
+            let cost_of = |range: &[Option<LightBounds>]| {
+                range
+                    .iter()
+                    .flatten()
+                    .fold(None, |acc: Option<LightBounds>, b| {
+                        Some(acc.map_or(*b, |acc| acc.union(b)))
+                    })
+                    .map_or(0.0, |b| b.cost())
+            };
+            let cost =
+                regularization * (cost_of(&buckets[..split_at]) + cost_of(&buckets[split_at..]));
+            match best {
+                Some((best_cost, _, _)) if best_cost <= cost => (),
+                _ => best = Some((cost, axis, split_at)),
+            }
+        }
+    }
+
+    let mid = match best {
+        Some((_, axis, split_at)) => partition(items, |b| bucket(b, axis) < split_at),
+        None => 0,
+    };
+    // Every light is in the same spot or all ended up on one side
+    if mid == 0 || mid == items.len() {
+        items.len() / 2
+    } else {
+        mid
+    }
+}
+
+fn partition(items: &mut [(usize, LightBounds)], left: impl Fn(&LightBounds) -> bool) -> usize {
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if left(&items[i].1) {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+impl Light for LightBvh {
+    fn sample(&self, p: Vec3) -> LightSample {
+        let mut pmf = 1.0;
+        let mut node = 0;
+
+        loop {
+            match self.nodes.get(node) {
+                Some(Node::Leaf { light, .. }) => {
+                    let sample = self.lights[*light].sample(p);
+                    return LightSample {
+                        radiance: sample.radiance / pmf,
+                        ..sample
+                    };
+                }
+                Some(Node::Interior { left, right, .. }) => {
+                    let left_importance = self.nodes[*left].bounds().importance(p);
+                    let right_importance = self.nodes[*right].bounds().importance(p);
+                    let total = left_importance + right_importance;
+                    if total <= 0.0 {
+                        break;
+                    }
+
+                    let p_left = left_importance / total;
+                    if sampler::random() < p_left {
+                        node = *left;
+                        pmf *= p_left;
+                    } else {
+                        node = *right;
+                        pmf *= 1.0 - p_left;
+                    }
+                }
+                // no lights at all
+                None => break,
+            }
+        }
+
+        LightSample {
+            direction: Vec3::new(0.0, 0.0, 1.0),
+            distance: 0.0,
+            radiance: Vec3::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    fn bounds(&self) -> LightBounds {
+        match self.nodes.first() {
+            Some(node) => *node.bounds(),
+            None => LightBounds::omnidirectional(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                0.0,
+            ),
+        }
+    }
+}
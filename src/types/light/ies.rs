@@ -0,0 +1,255 @@
+use crate::types::Vec3;
+
+/// Distribution of light from a real fixture, Loaded from an IES LM-63 photometric file.
+///
+/// Only type C photometry is supported, Which is what nearly all architectural fixtures use.
+/// Vertical angles are measured from the nadir(straight down the axis of the fixture)
+/// and horizontal angles around it.
+pub struct IesProfile {
+    /// in degrees, ascending
+    vertical_angles: Vec<f64>,
+    /// in degrees, ascending
+    horizontal_angles: Vec<f64>,
+    /// candela values for each of the horizontal angles, One per vertical angle
+    candela: Vec<Vec<f64>>,
+    max_candela: f64,
+}
+
+impl IesProfile {
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut lines = data.lines();
+
+        // Everything up to the TILT line is a free form header with keywords
+        let tilt = loop {
+            match lines.next() {
+                Some(line) if line.trim_start().starts_with("TILT=") => {
+                    break line.trim()[5..].trim()
+                }
+                Some(_) => continue,
+                None => return Err("ies file is missing the TILT line".to_string()),
+            }
+        };
+
+        let mut numbers = Numbers {
+            tokens: lines
+                .flat_map(|line| line.split(|c: char| c.is_ascii_whitespace() || c == ','))
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .into_iter(),
+        };
+
+        match tilt {
+            "NONE" => (),
+            "INCLUDE" => {
+                // Lamp to luminaire geometry, Followed by angle and multiplying factor pairs.
+                // Tilt only matters for lamps that change output with orientation so it's ignored
+                numbers.next()?;
+                let pairs = numbers.next()? as usize;
+                match pairs.checked_mul(2) {
+                    Some(count) if count <= numbers.remaining() => {
+                        for _ in 0..count {
+                            numbers.next()?;
+                        }
+                    }
+                    _ => return Err("ies file has more tilt angles than numbers".to_string()),
+                }
+            }
+            _ => {
+                return Err(
+                    "ies files with tilt data in a separate file are not supported".to_string(),
+                )
+            }
+        }
+
+        let _lamps = numbers.next()?;
+        let _lumens_per_lamp = numbers.next()?;
+        let multiplier = numbers.next()?;
+        let vertical_count = numbers.next()? as usize;
+        let horizontal_count = numbers.next()? as usize;
+        let photometric_type = numbers.next()?;
+        // units type, width, length and height of the luminous opening,
+        // ballast factor, future use and input watts
+        for _ in 0..7 {
+            numbers.next()?;
+        }
+        if photometric_type as u8 != 1 {
+            return Err("only type C photometry is supported".to_string());
+        }
+        if vertical_count == 0 || horizontal_count == 0 {
+            return Err("ies file has no candela values".to_string());
+        }
+        let count = vertical_count
+            .checked_mul(horizontal_count)
+            .and_then(|count| count.checked_add(vertical_count))
+            .and_then(|count| count.checked_add(horizontal_count));
+        if !matches!(count, Some(count) if count <= numbers.remaining()) {
+            return Err("ies file has less candela values than the header says".to_string());
+        }
+
+        let vertical_angles = (0..vertical_count)
+            .map(|_| numbers.next())
+            .collect::<Result<Vec<_>, _>>()?;
+        let horizontal_angles = (0..horizontal_count)
+            .map(|_| numbers.next())
+            .collect::<Result<Vec<_>, _>>()?;
+        let candela = (0..horizontal_count)
+            .map(|_| {
+                (0..vertical_count)
+                    .map(|_| numbers.next().map(|c| c * multiplier))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let max_candela = candela.iter().flatten().fold(0.0f64, |max, &c| max.max(c));
+
+        Ok(Self {
+            vertical_angles,
+            horizontal_angles,
+            candela,
+            max_candela,
+        })
+    }
+
+    pub fn max_candela(&self) -> f64 {
+        self.max_candela
+    }
+
+    /// Intensity towards `direction` relative to the brightest direction of the fixture
+    pub fn relative_intensity(&self, direction: Vec3) -> f64 {
+        if self.max_candela <= 0.0 {
+            return 0.0;
+        }
+        self.candela(direction) / self.max_candela
+    }
+
+    /// Luminous intensity in candela towards `direction`, Given in the frame of the fixture
+    /// where +z is the nadir and +x is the 0° horizontal angle. `direction` must be a unit vector
+    pub fn candela(&self, direction: Vec3) -> f64 {
+        let vertical = direction.z().clamp(-1.0, 1.0).acos().to_degrees();
+        let horizontal = direction.y().atan2(direction.x()).to_degrees();
+        let horizontal = self.fold_horizontal(if horizontal < 0.0 {
+            horizontal + 360.0
+        } else {
+            horizontal
+        });
+
+        let (h, h_t) = match lookup(&self.horizontal_angles, horizontal) {
+            Some(found) => found,
+            None => return 0.0,
+        };
+        let (v, v_t) = match lookup(&self.vertical_angles, vertical) {
+            Some(found) => found,
+            None => return 0.0,
+        };
+
+        let at = |h: usize| {
+            let row = &self.candela[h];
+            row[v] * (1.0 - v_t) + row[(v + 1).min(row.len() - 1)] * v_t
+        };
+        at(h) * (1.0 - h_t) + at((h + 1).min(self.candela.len() - 1)) * h_t
+    }
+
+    /// Files only store the horizontal angles needed for the symmetry of the fixture
+    fn fold_horizontal(&self, angle: f64) -> f64 {
+        let last = self.horizontal_angles[self.horizontal_angles.len() - 1];
+        if last <= 0.0 {
+            // same in every direction
+            0.0
+        } else if last <= 90.0 {
+            // symmetric in each quadrant
+            let angle = if angle > 180.0 { 360.0 - angle } else { angle };
+            if angle > 90.0 {
+                180.0 - angle
+            } else {
+                angle
+            }
+        } else if last <= 180.0 {
+            // symmetric about the 0-180 plane
+            if angle > 180.0 {
+                360.0 - angle
+            } else {
+                angle
+            }
+        } else {
+            angle
+        }
+    }
+}
+
+/// Numbers after the TILT line
+struct Numbers<'a> {
+    tokens: std::vec::IntoIter<&'a str>,
+}
+
+impl Numbers<'_> {
+    fn next(&mut self) -> Result<f64, String> {
+        let s = self.tokens.next().ok_or("unexpected end of ies file")?;
+        s.parse::<f64>()
+            .map_err(|e| format!("invalid number {} in ies file: {}", s, e))
+    }
+
+    fn remaining(&self) -> usize {
+        self.tokens.len()
+    }
+}
+
+/// Index of the last angle at or before `angle` and how far `angle` is towards the next one.
+/// `None` if it's outside the measured range
+fn lookup(angles: &[f64], angle: f64) -> Option<(usize, f64)> {
+    if angles.len() == 1 {
+        return Some((0, 0.0));
+    }
+    if angle < angles[0] || angle > angles[angles.len() - 1] {
+        return None;
+    }
+
+    let i = angles
+        .iter()
+        .rposition(|&a| a <= angle)
+        .unwrap_or(0)
+        .min(angles.len() - 2);
+    let span = angles[i + 1] - angles[i];
+    let t = if span > 0.0 {
+        (angle - angles[i]) / span
+    } else {
+        0.0
+    };
+    Some((i, t.clamp(0.0, 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IesProfile;
+
+    const DOWNLIGHT: &str = include_str!("../../../assets/downlight.ies");
+
+    #[test]
+    fn valid() {
+        let profile = IesProfile::parse(DOWNLIGHT).unwrap();
+        assert_eq!(profile.max_candela(), 2400.0);
+    }
+
+    #[test]
+    fn truncated() {
+        let end = DOWNLIGHT.trim_end().rfind(char::is_whitespace).unwrap();
+        assert!(IesProfile::parse(&DOWNLIGHT[..end]).is_err());
+        assert!(IesProfile::parse("IESNA:LM-63-2002").is_err());
+    }
+
+    #[test]
+    fn zero_sizes() {
+        let data = "TILT=NONE\n1 1000 1.0 0 1 1 2 0.1 0.1 0.0\n1.0 1.0 12\n0\n";
+        assert!(IesProfile::parse(data).is_err());
+    }
+
+    #[test]
+    fn overflowing_counts() {
+        let tilt = "TILT=INCLUDE\n1\n1e30\n0 1\n";
+        assert!(IesProfile::parse(tilt).is_err());
+        let data = format!(
+            "TILT=NONE\n1 1000 1.0 {} {} 1 2 0.1 0.1 0.0\n1.0 1.0 12\n0 0 1\n",
+            usize::MAX / 2,
+            4
+        );
+        assert!(IesProfile::parse(&data).is_err());
+    }
+}
@@ -1,10 +1,62 @@
-use {
-    crate::types::{HitRecord, Ray, Vec3},
-    rand::Rng,
-};
+mod cloth;
+mod coated;
+mod conductor;
+mod cutout;
+mod diffuse_light;
+mod isotropic;
+mod microfacet;
+mod mix;
+mod oren_nayar;
+mod principled;
+mod rough_dielectric;
+mod subsurface;
+mod thin_film;
+mod toon;
+
+pub use cloth::Cloth;
+pub use coated::Coated;
+pub use conductor::Conductor;
+pub use cutout::Cutout;
+pub use diffuse_light::DiffuseLight;
+pub use isotropic::Isotropic;
+pub use mix::MixMaterial;
+pub use oren_nayar::OrenNayar;
+pub use principled::Principled;
+pub use rough_dielectric::RoughDielectric;
+pub use subsurface::Subsurface;
+pub use thin_film::ThinFilm;
+pub use toon::Toon;
+
+use crate::types::{HitRecord, Ray, Sampler, Vec3};
 
 pub trait Material: Send + Sync {
-    fn scatter(&self, ray: &Ray, hit_rec: &HitRecord) -> (Vec3, Option<Ray>);
+    /// Picks the direction light bounces off in. Every random decision takes
+    /// the next dimension from `sampler`
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>);
+
+    /// How much of the surface is actually there at the hit point.
+    /// 1.0 is fully opaque, 0.0 is fully transparent and rays go right through it
+    fn opacity(&self, _hit_rec: &HitRecord) -> f64 {
+        1.0
+    }
+
+    /// Light given off by the surface towards where `ray` came from
+    fn emitted(&self, _ray: &Ray, _hit_rec: &HitRecord) -> Vec3 {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+
+    /// Fraction of the light arriving from `direction` that's reflected back along `ray`,
+    /// Including the cosine term. Used to light surfaces directly from lights that
+    /// rays can't hit. `None` for materials like mirrors and glass that only reflect
+    /// light from a single direction
+    fn eval(&self, _ray: &Ray, _hit_rec: &HitRecord, _direction: Vec3) -> Option<Vec3> {
+        None
+    }
 }
 
 pub struct Lambertian {
@@ -18,13 +70,22 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit_rec: &HitRecord) -> (Vec3, Option<Ray>) {
-        let mut rng = rand::thread_rng();
-        let target = hit_rec.p + hit_rec.normal + random_point_in_unit_sphere(&mut rng);
+    fn scatter(
+        &self,
+        _ray: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let target = hit_rec.p + hit_rec.normal + random_point_in_unit_sphere(sampler);
         let scattered_ray = Ray::new(hit_rec.p, target - hit_rec.p);
 
         (self.albedo, Some(scattered_ray))
     }
+
+    fn eval(&self, _ray: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let cosine = hit_rec.normal.dot(&direction).max(0.0);
+        Some(self.albedo * (cosine / std::f64::consts::PI))
+    }
 }
 
 pub struct Metal {
@@ -42,13 +103,16 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> (Vec3, Option<Ray>) {
-        let mut rng = rand::thread_rng();
-
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
         let reflected_ray = reflect(ray_in.direction().unit_vector(), hit_rec.normal);
         let scattered_ray = Ray::new(
             hit_rec.p,
-            reflected_ray + random_point_in_unit_sphere(&mut rng) * self.fuzz,
+            reflected_ray + random_point_in_unit_sphere(sampler) * self.fuzz,
         );
 
         if scattered_ray.direction().dot(&hit_rec.normal) > 0.0 {
@@ -61,20 +125,35 @@ impl Material for Metal {
 
 pub struct Dielectric {
     reflection_index: f64,
+    thin_film: Option<ThinFilm>,
 }
 
 impl Dielectric {
     pub fn new(reflection_index: f64) -> Self {
-        Self { reflection_index }
+        Self {
+            reflection_index,
+            thin_film: None,
+        }
+    }
+
+    /// Coats the surface with a thin film. A dielectric with reflection index of 1.0
+    /// and a film of water is a soap bubble
+    pub fn thin_film(mut self, thin_film: ThinFilm) -> Self {
+        self.thin_film = Some(thin_film);
+        self
     }
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord) -> (Vec3, Option<Ray>) {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
         let reflected_ray = reflect(ray_in.direction(), hit_rec.normal);
         // Glass absorbs nothing! So, Attenuation is always going to be 1.0 for this
         let attenuation = Vec3::new(1.0, 1.0, 1.0);
-        let mut rng = rand::thread_rng();
 
         let (outward_normal, ni_over_nt, cosine) = if ray_in.direction().dot(&hit_rec.normal) > 0.0
         {
@@ -93,9 +172,40 @@ impl Material for Dielectric {
         };
 
         if let Some(refracted_ray) = refract(ray_in.direction(), outward_normal, ni_over_nt) {
+            if let Some(thin_film) = &self.thin_film {
+                // Film reflectance is different for each channel. Pick reflection with the
+                // average probability and divide by it to keep the colors right.
+                // The film is treated the same from both sides, Which is exact for bubbles
+                let cosine =
+                    ray_in.direction().dot(&hit_rec.normal).abs() / ray_in.direction().length();
+                let reflectance = thin_film.reflectance(
+                    cosine,
+                    hit_rec,
+                    Vec3::new(
+                        self.reflection_index,
+                        self.reflection_index,
+                        self.reflection_index,
+                    ),
+                    Vec3::new(0.0, 0.0, 0.0),
+                );
+                let reflect_prob = (reflectance.r() + reflectance.g() + reflectance.b()) / 3.0;
+
+                return if sampler.get_1d() < reflect_prob {
+                    (
+                        reflectance / reflect_prob,
+                        Some(Ray::new(hit_rec.p, reflected_ray)),
+                    )
+                } else {
+                    (
+                        (Vec3::new(1.0, 1.0, 1.0) - reflectance) / (1.0 - reflect_prob),
+                        Some(Ray::new(hit_rec.p, refracted_ray)),
+                    )
+                };
+            }
+
             let reflect_prob = schlick(cosine, self.reflection_index);
 
-            if rng.gen::<f64>() < reflect_prob {
+            if sampler.get_1d() < reflect_prob {
                 (attenuation, Some(Ray::new(hit_rec.p, reflected_ray)))
             } else {
                 (attenuation, Some(Ray::new(hit_rec.p, refracted_ray)))
@@ -130,12 +240,14 @@ fn refract(incident: Vec3, normal: Vec3, ni_over_nt: f64) -> Option<Vec3> {
     }
 }
 
-fn random_point_in_unit_sphere(rng: &mut rand::rngs::ThreadRng) -> Vec3 {
-    let mut point = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 2.0
-        - Vec3::new(1.0, 1.0, 1.0);
-    while point.sq_len() >= 1.0 {
-        point = Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 2.0
-            - Vec3::new(1.0, 1.0, 1.0);
-    }
-    point
+// Uniform direction scaled by the cube root of a uniform number, So points are spread
+// evenly through the volume. Always takes three dimensions unlike rejection sampling
+fn random_point_in_unit_sphere(sampler: &mut dyn Sampler) -> Vec3 {
+    let (u1, u2) = sampler.get_2d();
+    let radius = sampler.get_1d().cbrt();
+
+    let z = 1.0 - 2.0 * u1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * std::f64::consts::PI * u2;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z) * radius
 }
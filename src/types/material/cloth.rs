@@ -0,0 +1,111 @@
+use {
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Vec3},
+    std::f64::consts::PI,
+};
+
+/// Fabrics like velvet, satin and felt.
+/// Fibers sticking out of the cloth scatter light back towards grazing angles which
+/// gives cloth it's soft rim. That's modelled with a sheen lobe added on top of a diffuse base.
+///
+/// See "Production Friendly Microfacet Sheen BRDF" by Alejandro Conty Estevez and Christopher Kulla
+pub struct Cloth {
+    albedo: Vec3,
+    sheen: Vec3,
+    /// roughness of the sheen lobe, clamped to 0.07..1
+    roughness: f64,
+}
+
+impl Cloth {
+    /// `sheen` is the color of the rim and `roughness` controls how wide it is.
+    /// Low roughness gives a thin bright rim like velvet, High roughness a soft one like felt
+    pub fn new(albedo: Vec3, sheen: Vec3, roughness: f64) -> Self {
+        Self {
+            albedo,
+            sheen,
+            roughness: roughness.clamp(0.07, 1.0),
+        }
+    }
+
+    /// "Charlie" sheen distribution, An inverted gaussian like distribution
+    /// where most of the fibers are perpendicular to the normal
+    fn d(&self, h: Vec3) -> f64 {
+        let inv_r = 1.0 / self.roughness;
+        let sin_theta = (1.0 - h.z() * h.z()).max(0.0).sqrt();
+
+        (2.0 + inv_r) * sin_theta.powf(inv_r) / (2.0 * PI)
+    }
+
+    /// Shadowing-masking term, Fitted to the lambda of the distribution
+    fn lambda(&self, cos_theta: f64) -> f64 {
+        let lambda = if cos_theta < 0.5 {
+            self.l(cos_theta).exp()
+        } else {
+            (2.0 * self.l(0.5) - self.l(1.0 - cos_theta)).exp()
+        };
+        // Softens the harsh shadow terminator the fit produces
+        lambda.powf(1.0 + 2.0 * (1.0 - cos_theta).powi(8))
+    }
+
+    fn l(&self, x: f64) -> f64 {
+        let t = (1.0 - self.roughness) * (1.0 - self.roughness);
+        let lerp = |at_0: f64, at_1: f64| t * at_0 + (1.0 - t) * at_1;
+
+        let a = lerp(25.3245, 21.5473);
+        let b = lerp(3.32435, 3.82987);
+        let c = lerp(0.16801, 0.19823);
+        let d = lerp(-1.27393, -1.97760);
+        let e = lerp(-4.85967, -4.32054);
+
+        a / (1.0 + b * x.powf(c)) + d * x + e
+    }
+
+    /// BRDF for directions in the local shading frame
+    fn bsdf(&self, wo: Vec3, wi: Vec3) -> Vec3 {
+        let h = (wo + wi).unit_vector();
+        let g = 1.0 / (1.0 + self.lambda(wo.z()) + self.lambda(wi.z()));
+        let sheen = self.d(h) * g / (4.0 * wo.z() * wi.z());
+
+        self.albedo / PI + self.sheen * sheen
+    }
+}
+
+impl Material for Cloth {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        if wo.z() <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        // The sheen lobe is wide enough that cosine weighted sampling works well for both lobes
+        let (u1, u2) = sampler.get_2d();
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let wi = Vec3::new(r * phi.cos(), r * phi.sin(), (1.0 - u1).sqrt());
+        if wi.z() <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        // cos / pdf = PI with cosine weighted sampling
+        (
+            self.bsdf(wo, wi) * PI,
+            Some(Ray::new(hit_rec.p, frame.to_world(wi))),
+        )
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        let wi = frame.to_local(direction);
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Some(Vec3::new(0.0, 0.0, 0.0));
+        }
+
+        Some(self.bsdf(wo, wi) * wi.z())
+    }
+}
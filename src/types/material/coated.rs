@@ -0,0 +1,178 @@
+use {
+    super::{
+        microfacet::{fresnel_dielectric, refract_through, Ggx},
+        reflect, refract,
+    },
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Vec3},
+};
+
+/// A layer of dielectric coating(varnish, clearcoat, glaze) on top of any other material.
+///
+/// Light either reflects off the coat with probability given by the fresnel term
+/// or refracts into the coat, interacts with the base material, gets absorbed a bit by the
+/// coat on it's way in and out and finally refracts back out.
+/// Light bouncing around between the base and the coat multiple times is ignored.
+pub struct Coated {
+    base: Box<dyn Material>,
+    reflection_index: f64,
+    /// `None` for a perfectly smooth coat
+    distribution: Option<Ggx>,
+    thickness: f64,
+    /// Fraction of light that makes it through a coat of unit thickness, per RGB channel
+    tint: Vec3,
+}
+
+impl Coated {
+    /// Smooth, clear coat with the given index of refraction
+    pub fn new(base: Box<dyn Material>, reflection_index: f64) -> Self {
+        Self {
+            base,
+            reflection_index,
+            distribution: None,
+            thickness: 0.0,
+            tint: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn roughness(mut self, roughness: f64) -> Self {
+        self.distribution = Some(Ggx::new(roughness));
+        self
+    }
+    pub fn thickness(mut self, thickness: f64) -> Self {
+        self.thickness = thickness;
+        self
+    }
+    pub fn tint(mut self, tint: Vec3) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Beer-Lambert absorption for a path of length `distance` inside the coat
+    fn absorption(&self, distance: f64) -> Vec3 {
+        Vec3::new(
+            self.tint.r().powf(distance),
+            self.tint.g().powf(distance),
+            self.tint.b().powf(distance),
+        )
+    }
+
+    /// Fraction of light that makes it through the coat once, Crossing the surface at an
+    /// angle with this cosine on the outside
+    fn transmittance(&self, cosine: f64) -> Vec3 {
+        let sin2_inside = (1.0 - cosine * cosine) / (self.reflection_index * self.reflection_index);
+        let cos_inside = (1.0 - sin2_inside).max(1e-6).sqrt();
+        self.absorption(self.thickness / cos_inside)
+            * (1.0 - fresnel_dielectric(cosine, self.reflection_index))
+    }
+}
+
+impl Material for Coated {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let direction = ray_in.direction().unit_vector();
+        let cos_i = -direction.dot(&hit_rec.normal);
+        // The coat is only on the outside
+        if cos_i <= 0.0 {
+            return self.base.scatter(ray_in, hit_rec, sampler);
+        }
+
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-direction);
+        let m = match &self.distribution {
+            Some(distribution) => {
+                let (u1, u2) = sampler.get_2d();
+                distribution.sample_visible_normal(wo, u1, u2)
+            }
+            None => Vec3::new(0.0, 0.0, 1.0),
+        };
+
+        // Reflection off the coat
+        if sampler.get_1d() < fresnel_dielectric(wo.dot(&m), self.reflection_index) {
+            let wi = reflect(-wo, m);
+            if wi.z() <= 0.0 {
+                return (Vec3::new(0.0, 0.0, 0.0), None);
+            }
+            let weight = match &self.distribution {
+                Some(distribution) => distribution.g2(wo, wi) / distribution.g1(wo),
+                None => 1.0,
+            };
+            return (
+                Vec3::new(weight, weight, weight),
+                Some(Ray::new(hit_rec.p, frame.to_world(wi))),
+            );
+        }
+
+        // Transmission into the coat through the same microfacet. Refraction at the
+        // boundary always happens so the picking probability cancels out the 1 - F term
+        let inside = match refract_through(wo, m, self.reflection_index) {
+            Some(inside) if inside.z() < 0.0 => inside,
+            _ => return (Vec3::new(0.0, 0.0, 0.0), None),
+        };
+        let weight = match &self.distribution {
+            Some(distribution) => distribution.g2(wo, inside) / distribution.g1(wo),
+            None => 1.0,
+        };
+        let inside = frame.to_world(inside).unit_vector();
+        let (attenuation, scattered) =
+            self.base
+                .scatter(&Ray::new(hit_rec.p, inside), hit_rec, sampler);
+        let scattered = match scattered {
+            Some(scattered) => scattered.direction().unit_vector(),
+            None => return (attenuation, None),
+        };
+
+        // Leave the coat through the average surface, So a rough coat only blurs light
+        // on the way in. Whatever is reflected back down at this point is dropped
+        let cos_out = scattered.dot(&hit_rec.normal);
+        if cos_out <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+        let outside = match refract(scattered, -hit_rec.normal, self.reflection_index) {
+            Some(outside) => outside,
+            None => return (Vec3::new(0.0, 0.0, 0.0), None),
+        };
+        let transmittance = 1.0 - fresnel_dielectric(cos_out, 1.0 / self.reflection_index);
+        let cos_in = -inside.dot(&hit_rec.normal);
+        let absorption = self.absorption(self.thickness * (1.0 / cos_in + 1.0 / cos_out));
+
+        (
+            attenuation * absorption * (transmittance * weight),
+            Some(Ray::new(hit_rec.p, outside)),
+        )
+    }
+
+    fn opacity(&self, hit_rec: &HitRecord) -> f64 {
+        self.base.opacity(hit_rec)
+    }
+
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        let emitted = self.base.emitted(ray_in, hit_rec);
+        let cosine = -ray_in.direction().unit_vector().dot(&hit_rec.normal);
+        if cosine <= 0.0 {
+            return emitted;
+        }
+        // Light from the base gets through the coat on it's way out
+        emitted * self.transmittance(cosine)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let base = self.base.eval(ray_in, hit_rec, direction)?;
+        let cos_o = -ray_in.direction().unit_vector().dot(&hit_rec.normal);
+        if cos_o <= 0.0 {
+            return Some(base);
+        }
+        let cos_i = direction.unit_vector().dot(&hit_rec.normal);
+        if cos_i <= 0.0 {
+            return Some(Vec3::new(0.0, 0.0, 0.0));
+        }
+
+        // Light goes through the coat to the base and back out. The base is evaluated
+        // without bending the directions at the coat. Reflections off the coat itself
+        // only show up through scattering
+        Some(base * self.transmittance(cos_i) * self.transmittance(cos_o))
+    }
+}
@@ -0,0 +1,164 @@
+use {
+    super::{
+        microfacet::{fresnel_conductor, Ggx},
+        reflect, ThinFilm,
+    },
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Texture, Vec3},
+};
+
+/// Direction the roughness along x of an anisotropic distribution follows
+enum Tangent {
+    /// tangent of the surface at the hit point
+    Surface,
+    /// same direction in world space everywhere
+    Constant(Vec3),
+    /// Tangent space direction stored in a texture the same way as in a normal map.
+    /// Only the x and y components are used
+    Map(Box<dyn Texture>),
+}
+
+/// Rough metal modelled with the GGX microfacet distribution.
+/// Unlike `Metal`, The reflectance comes from the complex index of refraction
+/// of the metal so the color shifts correctly towards grazing angles.
+pub struct Conductor {
+    /// real part of the index of refraction, per RGB channel
+    eta: Vec3,
+    /// extinction coefficient(imaginary part of the index of refraction), per RGB channel
+    k: Vec3,
+    distribution: Ggx,
+    tangent: Tangent,
+    thin_film: Option<ThinFilm>,
+}
+
+impl Conductor {
+    pub fn new(eta: Vec3, k: Vec3, roughness: f64) -> Self {
+        Self {
+            eta,
+            k,
+            distribution: Ggx::new(roughness),
+            tangent: Tangent::Surface,
+            thin_film: None,
+        }
+    }
+
+    /// Different roughness along and across the tangent for brushed metal.
+    /// Replaces the roughness the metal was created with
+    pub fn anisotropic(mut self, roughness_x: f64, roughness_y: f64) -> Self {
+        self.distribution = Ggx::anisotropic(roughness_x, roughness_y);
+        self
+    }
+    /// Brushes the metal along `tangent` instead of the tangent of the surface
+    pub fn tangent(mut self, tangent: Vec3) -> Self {
+        self.tangent = Tangent::Constant(tangent);
+        self
+    }
+    /// Brushes the metal along the direction stored in a tangent space map
+    pub fn tangent_map(mut self, map: Box<dyn Texture>) -> Self {
+        self.tangent = Tangent::Map(map);
+        self
+    }
+
+    /// Coats the metal with a thin oxide layer for the anodised or tempered look
+    pub fn thin_film(mut self, thin_film: ThinFilm) -> Self {
+        self.thin_film = Some(thin_film);
+        self
+    }
+
+    // Measured values sampled at roughly 650nm, 550nm and 450nm for the R, G, B channels
+    pub fn gold(roughness: f64) -> Self {
+        Self::new(
+            Vec3::new(0.143, 0.374, 1.442),
+            Vec3::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+    pub fn copper(roughness: f64) -> Self {
+        Self::new(
+            Vec3::new(0.200, 0.924, 1.102),
+            Vec3::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+    pub fn aluminium(roughness: f64) -> Self {
+        Self::new(
+            Vec3::new(1.657, 0.880, 0.521),
+            Vec3::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+    pub fn silver(roughness: f64) -> Self {
+        Self::new(
+            Vec3::new(0.155, 0.117, 0.138),
+            Vec3::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
+    fn tangent_at(&self, hit_rec: &HitRecord) -> Vec3 {
+        match &self.tangent {
+            Tangent::Surface => hit_rec.tangent,
+            Tangent::Constant(tangent) => *tangent,
+            Tangent::Map(map) => {
+                let local =
+                    map.value(hit_rec.u, hit_rec.v, hit_rec.p) * 2.0 - Vec3::new(1.0, 1.0, 1.0);
+                let bitangent = hit_rec.normal.cross(&hit_rec.tangent);
+                hit_rec.tangent * local.x() + bitangent * local.y()
+            }
+        }
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let frame = Onb::from_w_u(hit_rec.normal, self.tangent_at(hit_rec));
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        // Metals are opaque, Nothing to do if we somehow ended up on the inside
+        if wo.z() <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        let (u1, u2) = sampler.get_2d();
+        let m = self.distribution.sample_visible_normal(wo, u1, u2);
+        let wi = reflect(-wo, m);
+        if wi.z() <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        // With visible normal sampling, f * cos / pdf reduces to F * G2 / G1.
+        // D and the jacobian of the reflection cancel out
+        let fresnel = match &self.thin_film {
+            Some(thin_film) => thin_film.reflectance(wo.dot(&m), hit_rec, self.eta, self.k),
+            None => fresnel_conductor(wo.dot(&m), self.eta, self.k),
+        };
+        let weight = self.distribution.g2(wo, wi) / self.distribution.g1(wo);
+
+        (
+            fresnel * weight,
+            Some(Ray::new(hit_rec.p, frame.to_world(wi))),
+        )
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let frame = Onb::from_w_u(hit_rec.normal, self.tangent_at(hit_rec));
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        let wi = frame.to_local(direction);
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Some(Vec3::new(0.0, 0.0, 0.0));
+        }
+
+        let m = (wo + wi).unit_vector();
+        let fresnel = match &self.thin_film {
+            Some(thin_film) => thin_film.reflectance(wo.dot(&m), hit_rec, self.eta, self.k),
+            None => fresnel_conductor(wo.dot(&m), self.eta, self.k),
+        };
+        // f * cos = F * D * G2 / (4 * cos_o * cos_i) * cos_i
+        let specular = self.distribution.d(m) * self.distribution.g2(wo, wi) / (4.0 * wo.z());
+
+        Some(fresnel * specular)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::types::{HitRecord, Material, Ray, Sampler, Texture, Vec3};
+
+/// Makes parts of any material see through using an opacity mask,
+/// For leaves, fences, decals etc. Light passes straight through the transparent parts
+/// without refracting.
+pub struct Cutout {
+    material: Box<dyn Material>,
+    /// red channel is the opacity, 0 is fully transparent and 1 is fully opaque
+    opacity: Box<dyn Texture>,
+    /// With alpha testing, Anything below the threshold is fully transparent and the rest
+    /// is fully opaque. Without it, Rays pass through with probability 1 - opacity
+    threshold: Option<f64>,
+}
+
+impl Cutout {
+    pub fn new(material: Box<dyn Material>, opacity: Box<dyn Texture>) -> Self {
+        Self {
+            material,
+            opacity,
+            threshold: None,
+        }
+    }
+
+    pub fn alpha_test(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        self.material.scatter(ray_in, hit_rec, sampler)
+    }
+
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        self.material.emitted(ray_in, hit_rec)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        self.material.eval(ray_in, hit_rec, direction)
+    }
+
+    fn opacity(&self, hit_rec: &HitRecord) -> f64 {
+        let opacity = self.opacity.value(hit_rec.u, hit_rec.v, hit_rec.p).r();
+        match self.threshold {
+            Some(threshold) if opacity < threshold => 0.0,
+            Some(_) => 1.0,
+            None => opacity,
+        }
+    }
+}
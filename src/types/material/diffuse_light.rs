@@ -0,0 +1,64 @@
+use {
+    crate::types::{
+        light::{IesProfile, Profile},
+        spectrum::{self, LUMENS_PER_WATT},
+        HitRecord, Material, Ray, Sampler, Vec3,
+    },
+    std::f64::consts::PI,
+};
+
+/// Surface that gives off the same light in every direction and doesn't reflect any
+pub struct DiffuseLight {
+    emit: Vec3,
+    profile: Option<Profile>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Vec3) -> Self {
+        Self {
+            emit,
+            profile: None,
+        }
+    }
+
+    /// Glowing like a blackbody at `kelvin`. `luminance` is the brightness of the emission,
+    /// The same scale as the colors everywhere else
+    pub fn blackbody(kelvin: f64, luminance: f64) -> Self {
+        Self::new(spectrum::blackbody(kelvin) * luminance)
+    }
+
+    /// Lamp with color temperature `kelvin` putting out `lumens` in total
+    /// from a surface of `area` square units. One unit in the scene is one meter.
+    ///
+    /// i.e. `DiffuseLight::lamp(3200.0, 800.0, sphere_area)` for a 60W incandescent bulb
+    pub fn lamp(kelvin: f64, lumens: f64, area: f64) -> Self {
+        // A lambertian emitter with radiance L gives off a flux of PI * L * area
+        let nits = lumens / (PI * area);
+        Self::blackbody(kelvin, nits / LUMENS_PER_WATT)
+    }
+
+    /// Shapes the emission like a real fixture pointing along `axis`.
+    /// The emission is what the light gives off in the brightest direction of the profile
+    pub fn profile(mut self, profile: IesProfile, axis: Vec3) -> Self {
+        self.profile = Some(Profile::new(profile, axis));
+        self
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _ray_in: &Ray,
+        _hit_rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        (Vec3::new(0.0, 0.0, 0.0), None)
+    }
+
+    fn emitted(&self, ray_in: &Ray, _hit_rec: &HitRecord) -> Vec3 {
+        match &self.profile {
+            Some(profile) => self.emit * profile.scale(-ray_in.direction().unit_vector()),
+            None => self.emit,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use {
+    super::random_point_in_unit_sphere,
+    crate::types::{HitRecord, Material, Ray, Sampler, Vec3},
+};
+
+/// Phase function of participating media that scatters light equally in all directions
+pub struct Isotropic {
+    albedo: Vec3,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Vec3) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        _ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let direction = random_point_in_unit_sphere(sampler).unit_vector();
+
+        (self.albedo, Some(Ray::new(hit_rec.p, direction)))
+    }
+
+    fn eval(&self, _ray_in: &Ray, _hit_rec: &HitRecord, _direction: Vec3) -> Option<Vec3> {
+        // There's no cosine term inside a medium
+        Some(self.albedo / (4.0 * std::f64::consts::PI))
+    }
+}
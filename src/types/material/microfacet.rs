@@ -0,0 +1,174 @@
+use crate::types::Vec3;
+
+/// Trowbridge-Reitz(GGX) microfacet distribution.
+///
+/// All the directions passed to these methods are in the local shading frame,
+/// i.e. the macro surface normal is (0, 0, 1)
+pub struct Ggx {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl Ggx {
+    /// `roughness` is the perceptually linear roughness in 0..1 range.
+    /// It's squared to get the alpha used by the distribution
+    pub fn new(roughness: f64) -> Self {
+        let alpha = roughness_to_alpha(roughness);
+        Self {
+            alpha_x: alpha,
+            alpha_y: alpha,
+        }
+    }
+
+    /// Stretched distribution with different roughness along the x(tangent) and
+    /// y(bitangent) axes of the shading frame
+    pub fn anisotropic(roughness_x: f64, roughness_y: f64) -> Self {
+        Self {
+            alpha_x: roughness_to_alpha(roughness_x),
+            alpha_y: roughness_to_alpha(roughness_y),
+        }
+    }
+
+    /// Density of microfacets with normal `m`
+    pub fn d(&self, m: Vec3) -> f64 {
+        let x = m.x() / self.alpha_x;
+        let y = m.y() / self.alpha_y;
+        let t = x * x + y * y + m.z() * m.z();
+
+        1.0 / (std::f64::consts::PI * self.alpha_x * self.alpha_y * t * t)
+    }
+
+    /// Smith's auxiliary function for the GGX distribution
+    fn lambda(&self, w: Vec3) -> f64 {
+        let cos2 = w.z() * w.z();
+        if cos2 == 0.0 {
+            return f64::INFINITY;
+        }
+        let ax = self.alpha_x * w.x();
+        let ay = self.alpha_y * w.y();
+        let tan2_alpha2 = (ax * ax + ay * ay) / cos2;
+
+        ((1.0 + tan2_alpha2).sqrt() - 1.0) * 0.5
+    }
+
+    /// Smith masking function. Fraction of microfacets visible from `w`
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Height correlated Smith shadowing-masking function
+    pub fn g2(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Pdf of sampling `m` with `sample_visible_normal`
+    pub fn visible_normal_pdf(&self, wo: Vec3, m: Vec3) -> f64 {
+        if wo.z() <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * wo.dot(&m).max(0.0) * self.d(m) / wo.z()
+    }
+
+    /// Samples a microfacet normal from the distribution of normals visible from `wo`.
+    /// `wo` has to be in the upper hemisphere.
+    ///
+    /// See "Sampling the GGX Distribution of Visible Normals" by Eric Heitz,
+    /// http://jcgt.org/published/0007/04/01/
+    pub fn sample_visible_normal(&self, wo: Vec3, u1: f64, u2: f64) -> Vec3 {
+        // Stretch the view vector so we are sampling as if roughness = 1
+        let vh = Vec3::new(self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()).unit_vector();
+
+        // Orthonormal basis around the view vector
+        let len_sq = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if len_sq > 0.0 {
+            Vec3::new(-vh.y(), vh.x(), 0.0) / len_sq.sqrt()
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(&t1);
+
+        // Uniformly sample a point on the projected area of the hemisphere
+        let r = u1.sqrt();
+        let phi = 2.0 * std::f64::consts::PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+
+        // Reproject it on the hemisphere and unstretch it
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        Vec3::new(
+            self.alpha_x * nh.x(),
+            self.alpha_y * nh.y(),
+            nh.z().max(0.0),
+        )
+        .unit_vector()
+    }
+}
+
+// Alpha values too close to 0 make the distribution a dirac delta and break the math
+fn roughness_to_alpha(roughness: f64) -> f64 {
+    (roughness * roughness).max(1e-4)
+}
+
+/// Fresnel reflectance of a conductor with complex index of refraction eta + i * k
+/// for unpolarized light. Evaluated separately for each of the RGB channels.
+///
+/// See https://seblagarde.wordpress.com/2013/04/29/memo-on-fresnel-equations/
+pub fn fresnel_conductor(cos_i: f64, eta: Vec3, k: Vec3) -> Vec3 {
+    Vec3::new(
+        fresnel_conductor_channel(cos_i, eta.x(), k.x()),
+        fresnel_conductor_channel(cos_i, eta.y(), k.y()),
+        fresnel_conductor_channel(cos_i, eta.z(), k.z()),
+    )
+}
+
+fn fresnel_conductor_channel(cos_i: f64, eta: f64, k: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let cos2 = cos_i * cos_i;
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cos_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+/// Fresnel reflectance at a boundary between two dielectrics for unpolarized light.
+/// `eta` is the ratio of the index of refraction on the transmitted side to the incident side.
+/// Returns 1.0 when there is total internal reflection
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    let rs = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    let rp = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+
+    0.5 * (rs * rs + rp * rp)
+}
+
+/// Refracts `wo` through a microfacet with normal `m`. Both vectors point away from the surface
+/// and are on the same side of `m`. Returns `None` in case of total internal reflection
+pub fn refract_through(wo: Vec3, m: Vec3, eta: f64) -> Option<Vec3> {
+    let cos_i = wo.dot(&m);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    Some(-wo / eta + m * (cos_i / eta - cos_t))
+}
@@ -0,0 +1,117 @@
+use {
+    super::microfacet::fresnel_dielectric,
+    crate::types::{HitRecord, Material, Ray, Sampler, Texture, Vec3},
+};
+
+enum Weight {
+    Constant(f64),
+    /// red channel of the texture is the weight
+    Mask(Box<dyn Texture>),
+    /// weight is the fresnel reflectance of a dielectric with this index of refraction
+    Fresnel(f64),
+}
+
+/// Combines two materials on one surface. Each time a ray hits the surface,
+/// One of the materials is picked at random with probability given by the weight.
+/// Since that probability is the same as the contribution of the material,
+/// Attenuation from the picked material is used as is.
+pub struct MixMaterial {
+    first: Box<dyn Material>,
+    second: Box<dyn Material>,
+    weight: Weight,
+}
+
+impl MixMaterial {
+    /// `weight` is the fraction of `second` in the mix
+    pub fn new(first: Box<dyn Material>, second: Box<dyn Material>, weight: f64) -> Self {
+        Self {
+            first,
+            second,
+            weight: Weight::Constant(weight),
+        }
+    }
+
+    /// Uses `second` where the mask is white and `first` where it's black
+    pub fn with_mask(
+        first: Box<dyn Material>,
+        second: Box<dyn Material>,
+        mask: Box<dyn Texture>,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            weight: Weight::Mask(mask),
+        }
+    }
+
+    /// Uses more of `second` at grazing angles, Like a glossy layer on top of `first`
+    pub fn fresnel(
+        first: Box<dyn Material>,
+        second: Box<dyn Material>,
+        reflection_index: f64,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            weight: Weight::Fresnel(reflection_index),
+        }
+    }
+}
+
+impl MixMaterial {
+    fn weight(&self, ray_in: &Ray, hit_rec: &HitRecord) -> f64 {
+        let cosine = -ray_in.direction().unit_vector().dot(&hit_rec.normal);
+        self.weight_at(hit_rec, cosine.abs())
+    }
+
+    /// Weight for a ray hitting the surface at an angle with this cosine
+    fn weight_at(&self, hit_rec: &HitRecord, cosine: f64) -> f64 {
+        match &self.weight {
+            Weight::Constant(weight) => *weight,
+            Weight::Mask(mask) => mask.value(hit_rec.u, hit_rec.v, hit_rec.p).r(),
+            Weight::Fresnel(reflection_index) => fresnel_dielectric(cosine, *reflection_index),
+        }
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        if sampler.get_1d() < self.weight(ray_in, hit_rec) {
+            self.second.scatter(ray_in, hit_rec, sampler)
+        } else {
+            self.first.scatter(ray_in, hit_rec, sampler)
+        }
+    }
+
+    fn opacity(&self, hit_rec: &HitRecord) -> f64 {
+        // There's no ray here, So the fresnel weight is taken looking at the surface head on
+        let weight = self.weight_at(hit_rec, 1.0);
+        self.first.opacity(hit_rec) * (1.0 - weight) + self.second.opacity(hit_rec) * weight
+    }
+
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        let weight = self.weight(ray_in, hit_rec);
+        self.first.emitted(ray_in, hit_rec) * (1.0 - weight)
+            + self.second.emitted(ray_in, hit_rec) * weight
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let weight = self.weight(ray_in, hit_rec);
+        // A material that can't be evaluated adds nothing, It's lit by scattering instead
+        match (
+            self.first.eval(ray_in, hit_rec, direction),
+            self.second.eval(ray_in, hit_rec, direction),
+        ) {
+            (None, None) => None,
+            (first, second) => {
+                let zero = Vec3::new(0.0, 0.0, 0.0);
+                Some(first.unwrap_or(zero) * (1.0 - weight) + second.unwrap_or(zero) * weight)
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use {
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Vec3},
+    std::f64::consts::PI,
+};
+
+/// Rough diffuse surfaces like clay, concrete and cloth.
+/// The surface is made of tiny lambertian V shaped grooves so unlike `Lambertian`,
+/// It reflects more light back towards the light source and looks flatter.
+///
+/// See "Generalization of Lambert's Reflectance Model" by Oren and Nayar
+pub struct OrenNayar {
+    albedo: Vec3,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    /// `sigma` is the standard deviation of the angle of the grooves, in degrees.
+    /// With `sigma` = 0 this is the same as `Lambertian`
+    pub fn new(albedo: Vec3, sigma: f64) -> Self {
+        let sigma = sigma * PI / 180.0;
+        let sigma2 = sigma * sigma;
+
+        Self {
+            albedo,
+            a: 1.0 - sigma2 / (2.0 * (sigma2 + 0.33)),
+            b: 0.45 * sigma2 / (sigma2 + 0.09),
+        }
+    }
+
+    /// BRDF without the albedo / PI term, for directions in the local shading frame
+    fn weight(&self, wo: Vec3, wi: Vec3) -> f64 {
+        let sin_theta_i = (1.0 - wi.z() * wi.z()).max(0.0).sqrt();
+        let sin_theta_o = (1.0 - wo.z() * wo.z()).max(0.0).sqrt();
+
+        // cos(phi_i - phi_o), Only the grooves facing both the directions matter
+        let max_cos = if sin_theta_i > 1e-4 && sin_theta_o > 1e-4 {
+            ((wi.x() * wo.x() + wi.y() * wo.y()) / (sin_theta_i * sin_theta_o)).max(0.0)
+        } else {
+            0.0
+        };
+
+        // alpha = max(theta_i, theta_o), beta = min(theta_i, theta_o)
+        let (sin_alpha, tan_beta) = if wi.z() > wo.z() {
+            (sin_theta_o, sin_theta_i / wi.z())
+        } else {
+            (sin_theta_i, sin_theta_o / wo.z())
+        };
+
+        self.a + self.b * max_cos * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        if wo.z() <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        // cosine weighted hemisphere sampling
+        let (u1, u2) = sampler.get_2d();
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let wi = Vec3::new(r * phi.cos(), r * phi.sin(), (1.0 - u1).sqrt());
+
+        // f = albedo / PI * (A + B * ...) and cos / pdf = PI with cosine weighted sampling
+        (
+            self.albedo * self.weight(wo, wi),
+            Some(Ray::new(hit_rec.p, frame.to_world(wi))),
+        )
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        let wi = frame.to_local(direction);
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Some(Vec3::new(0.0, 0.0, 0.0));
+        }
+
+        Some(self.albedo * (self.weight(wo, wi) * wi.z() / PI))
+    }
+}
@@ -0,0 +1,317 @@
+use {
+    super::{
+        microfacet::{fresnel_dielectric, refract_through, Ggx},
+        reflect,
+    },
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Vec3},
+    std::f64::consts::PI,
+};
+
+/// Disney's principled BSDF.
+/// One material with artist friendly parameters that covers plastics, metals, car paint,
+/// fabrics and glass. Every parameter except `base_color` and `ior` is in 0..1 range.
+///
+/// See "Physically Based Shading at Disney" by Brent Burley and
+/// https://github.com/wdas/brdf/blob/main/src/brdfs/disney.brdf
+pub struct Principled {
+    base_color: Vec3,
+    metallic: f64,
+    roughness: f64,
+    specular: f64,
+    specular_tint: f64,
+    sheen: f64,
+    sheen_tint: f64,
+    clearcoat: f64,
+    clearcoat_gloss: f64,
+    subsurface: f64,
+    transmission: f64,
+    ior: f64,
+    distribution: Ggx,
+}
+
+impl Principled {
+    pub fn new(base_color: Vec3) -> Self {
+        Self {
+            base_color,
+            metallic: 0.0,
+            roughness: 0.5,
+            specular: 0.5,
+            specular_tint: 0.0,
+            sheen: 0.0,
+            sheen_tint: 0.5,
+            clearcoat: 0.0,
+            clearcoat_gloss: 1.0,
+            subsurface: 0.0,
+            transmission: 0.0,
+            ior: 1.5,
+            distribution: Ggx::new(0.5),
+        }
+    }
+
+    pub fn metallic(mut self, metallic: f64) -> Self {
+        self.metallic = metallic;
+        self
+    }
+    pub fn roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness;
+        self.distribution = Ggx::new(roughness);
+        self
+    }
+    pub fn specular(mut self, specular: f64) -> Self {
+        self.specular = specular;
+        self
+    }
+    pub fn specular_tint(mut self, specular_tint: f64) -> Self {
+        self.specular_tint = specular_tint;
+        self
+    }
+    pub fn sheen(mut self, sheen: f64) -> Self {
+        self.sheen = sheen;
+        self
+    }
+    pub fn sheen_tint(mut self, sheen_tint: f64) -> Self {
+        self.sheen_tint = sheen_tint;
+        self
+    }
+    pub fn clearcoat(mut self, clearcoat: f64) -> Self {
+        self.clearcoat = clearcoat;
+        self
+    }
+    pub fn clearcoat_gloss(mut self, clearcoat_gloss: f64) -> Self {
+        self.clearcoat_gloss = clearcoat_gloss;
+        self
+    }
+    pub fn subsurface(mut self, subsurface: f64) -> Self {
+        self.subsurface = subsurface;
+        self
+    }
+    pub fn transmission(mut self, transmission: f64) -> Self {
+        self.transmission = transmission;
+        self
+    }
+    pub fn ior(mut self, ior: f64) -> Self {
+        self.ior = ior;
+        self
+    }
+
+    /// Evaluates the reflective part of the BSDF(everything except transmission).
+    /// Both directions are in the local shading frame and point away from the surface
+    fn bsdf(&self, wo: Vec3, wi: Vec3) -> Vec3 {
+        let n_dot_l = wi.z();
+        let n_dot_v = wo.z();
+        if n_dot_l <= 0.0 || n_dot_v <= 0.0 {
+            return Vec3::new(0.0, 0.0, 0.0);
+        }
+        let h = (wo + wi).unit_vector();
+        let l_dot_h = wi.dot(&h);
+        let white = Vec3::new(1.0, 1.0, 1.0);
+
+        let luminance = self.base_color.dot(&Vec3::new(0.3, 0.6, 0.1));
+        let tint = if luminance > 0.0 {
+            self.base_color / luminance
+        } else {
+            white
+        };
+        let specular_color = mix(
+            mix(white, tint, self.specular_tint) * self.specular * 0.08,
+            self.base_color,
+            self.metallic,
+        );
+        let sheen_color = mix(white, tint, self.sheen_tint);
+
+        // Diffuse with retro reflection at grazing angles
+        let fl = schlick_weight(n_dot_l);
+        let fv = schlick_weight(n_dot_v);
+        let fd90 = 0.5 + 2.0 * l_dot_h * l_dot_h * self.roughness;
+        let fd = (1.0 + (fd90 - 1.0) * fl) * (1.0 + (fd90 - 1.0) * fv);
+
+        // Hanrahan-Krueger inspired approximation of subsurface scattering
+        let fss90 = l_dot_h * l_dot_h * self.roughness;
+        let fss = (1.0 + (fss90 - 1.0) * fl) * (1.0 + (fss90 - 1.0) * fv);
+        let ss = 1.25 * (fss * (1.0 / (n_dot_l + n_dot_v) - 0.5) + 0.5);
+
+        let fh = schlick_weight(l_dot_h);
+        let sheen = sheen_color * fh * self.sheen;
+        let diffuse = (self.base_color * (fd + (ss - fd) * self.subsurface) / PI + sheen)
+            * (1.0 - self.metallic);
+
+        let specular = mix(specular_color, white, fh)
+            * (self.distribution.d(h) * self.distribution.g2(wo, wi) / (4.0 * n_dot_l * n_dot_v));
+
+        // Clearcoat uses a fixed IOR of 1.5(F0 = 0.04) and a fixed roughness of 0.25 for G.
+        // smith_g_ggx already includes the 1 / (4 * n.l * n.v) term
+        let clearcoat = 0.25
+            * self.clearcoat
+            * gtr1(h.z(), self.clearcoat_alpha())
+            * (0.04 + 0.96 * fh)
+            * smith_g_ggx(n_dot_l, 0.25)
+            * smith_g_ggx(n_dot_v, 0.25);
+
+        diffuse + specular + Vec3::new(clearcoat, clearcoat, clearcoat)
+    }
+
+    /// Probability of sampling the diffuse, specular and clearcoat lobes respectively
+    fn lobe_probabilities(&self) -> (f64, f64, f64) {
+        let diffuse = 1.0 - self.metallic;
+        let specular = 1.0;
+        let clearcoat = 0.25 * self.clearcoat;
+        let total = diffuse + specular + clearcoat;
+
+        (diffuse / total, specular / total, clearcoat / total)
+    }
+
+    /// Pdf of `sample` picking `wi`. It's the average of the pdfs of all the lobes
+    /// weighted by the probability of picking that lobe(one sample MIS)
+    fn pdf(&self, wo: Vec3, wi: Vec3) -> f64 {
+        if wi.z() <= 0.0 || wo.z() <= 0.0 {
+            return 0.0;
+        }
+        let (p_diffuse, p_specular, p_clearcoat) = self.lobe_probabilities();
+        let h = (wo + wi).unit_vector();
+        let o_dot_h = wo.dot(&h);
+
+        let diffuse = wi.z() / PI;
+        let specular = self.distribution.visible_normal_pdf(wo, h) / (4.0 * o_dot_h);
+        let clearcoat = gtr1(h.z(), self.clearcoat_alpha()) * h.z() / (4.0 * o_dot_h);
+
+        p_diffuse * diffuse + p_specular * specular + p_clearcoat * clearcoat
+    }
+
+    fn sample(&self, wo: Vec3, sampler: &mut dyn Sampler) -> Vec3 {
+        let (p_diffuse, p_specular, _) = self.lobe_probabilities();
+        let lobe = sampler.get_1d();
+        let (u1, u2) = sampler.get_2d();
+        if lobe < p_diffuse {
+            // cosine weighted hemisphere
+            let r = u1.sqrt();
+            let phi = 2.0 * PI * u2;
+            Vec3::new(r * phi.cos(), r * phi.sin(), (1.0 - u1).sqrt())
+        } else if lobe < p_diffuse + p_specular {
+            let m = self.distribution.sample_visible_normal(wo, u1, u2);
+            reflect(-wo, m)
+        } else {
+            let alpha2 = self.clearcoat_alpha() * self.clearcoat_alpha();
+            let cos_theta = ((1.0 - alpha2.powf(1.0 - u1)) / (1.0 - alpha2))
+                .max(0.0)
+                .sqrt();
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let phi = 2.0 * PI * u2;
+            let m = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+            reflect(-wo, m)
+        }
+    }
+
+    /// Refraction through the rough glass part of the material.
+    /// `wo` is in the local frame and may be on either side of the surface
+    fn transmit(&self, wo: Vec3, sampler: &mut dyn Sampler) -> (Vec3, Option<Vec3>) {
+        let (wo, eta, side) = if wo.z() > 0.0 {
+            (wo, self.ior, 1.0)
+        } else {
+            (-wo, 1.0 / self.ior, -1.0)
+        };
+
+        let (u1, u2) = sampler.get_2d();
+        let m = self.distribution.sample_visible_normal(wo, u1, u2);
+
+        // Same as RoughDielectric, Except the color is picked up on each refraction.
+        // Using the square root here gives the base color for a full pass through an object
+        let (wi, attenuation) = if sampler.get_1d() < fresnel_dielectric(wo.dot(&m), eta) {
+            let wi = reflect(-wo, m);
+            if wi.z() <= 0.0 {
+                return (Vec3::new(0.0, 0.0, 0.0), None);
+            }
+            (wi, Vec3::new(1.0, 1.0, 1.0))
+        } else {
+            match refract_through(wo, m, eta) {
+                Some(wi) if wi.z() < 0.0 => (
+                    wi,
+                    Vec3::new(
+                        self.base_color.r().sqrt(),
+                        self.base_color.g().sqrt(),
+                        self.base_color.b().sqrt(),
+                    ),
+                ),
+                _ => return (Vec3::new(0.0, 0.0, 0.0), None),
+            }
+        };
+
+        let weight = self.distribution.g2(wo, wi) / self.distribution.g1(wo);
+        (attenuation * weight, Some(wi * side))
+    }
+
+    fn clearcoat_alpha(&self) -> f64 {
+        0.1 + (0.001 - 0.1) * self.clearcoat_gloss
+    }
+}
+
+impl Material for Principled {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+
+        // The glass part is picked with probability equal to it's weight in the BSDF so
+        // the weights cancel out. Rays traveling inside the object can only have come
+        // from the glass part so those are always refracted.
+        let transmission = self.transmission * (1.0 - self.metallic);
+        if wo.z() <= 0.0 || sampler.get_1d() < transmission {
+            let (attenuation, wi) = self.transmit(wo, sampler);
+            return (
+                attenuation,
+                wi.map(|wi| Ray::new(hit_rec.p, frame.to_world(wi))),
+            );
+        }
+
+        let wi = self.sample(wo, sampler);
+        let pdf = self.pdf(wo, wi);
+        if wi.z() <= 0.0 || pdf <= 0.0 {
+            return (Vec3::new(0.0, 0.0, 0.0), None);
+        }
+
+        (
+            self.bsdf(wo, wi) * (wi.z() / pdf),
+            Some(Ray::new(hit_rec.p, frame.to_world(wi))),
+        )
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Option<Vec3> {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+        let wi = frame.to_local(direction);
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Some(Vec3::new(0.0, 0.0, 0.0));
+        }
+
+        // The glass part only transmits light from a single direction
+        let transmission = self.transmission * (1.0 - self.metallic);
+        Some(self.bsdf(wo, wi) * (wi.z() * (1.0 - transmission)))
+    }
+}
+
+fn mix(a: Vec3, b: Vec3, t: f64) -> Vec3 {
+    a * (1.0 - t) + b * t
+}
+
+fn schlick_weight(cos: f64) -> f64 {
+    (1.0 - cos).clamp(0.0, 1.0).powi(5)
+}
+
+/// Generalized Trowbridge-Reitz distribution with gamma = 1, Used for the clearcoat lobe
+fn gtr1(n_dot_h: f64, alpha: f64) -> f64 {
+    if alpha >= 1.0 {
+        return 1.0 / PI;
+    }
+    let alpha2 = alpha * alpha;
+    let t = 1.0 + (alpha2 - 1.0) * n_dot_h * n_dot_h;
+    (alpha2 - 1.0) / (PI * alpha2.ln() * t)
+}
+
+fn smith_g_ggx(n_dot_v: f64, alpha: f64) -> f64 {
+    let a = alpha * alpha;
+    let b = n_dot_v * n_dot_v;
+    1.0 / (n_dot_v + (a + b - a * b).sqrt())
+}
@@ -0,0 +1,80 @@
+use {
+    super::{
+        microfacet::{fresnel_dielectric, refract_through, Ggx},
+        reflect,
+    },
+    crate::types::{HitRecord, Material, Onb, Ray, Sampler, Vec3},
+};
+
+/// Glass with a rough surface(frosted glass). Reflection and transmission are both
+/// modelled with the GGX microfacet BSDF from
+/// "Microfacet Models for Refraction through Rough Surfaces" by Walter et al.
+pub struct RoughDielectric {
+    reflection_index: f64,
+    distribution: Ggx,
+    /// color picked up by light every time it's transmitted through the surface
+    tint: Vec3,
+}
+
+impl RoughDielectric {
+    pub fn new(reflection_index: f64, roughness: f64) -> Self {
+        Self::with_tint(reflection_index, roughness, Vec3::new(1.0, 1.0, 1.0))
+    }
+    pub fn with_tint(reflection_index: f64, roughness: f64, tint: Vec3) -> Self {
+        Self {
+            reflection_index,
+            distribution: Ggx::new(roughness),
+            tint,
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        let frame = Onb::from_w(hit_rec.normal);
+        let wo = frame.to_local(-ray_in.direction().unit_vector());
+
+        // Do all the work on the side the ray came from. If the ray is leaving
+        // the object, The frame is flipped and the ratio of indices is inverted
+        let (wo, eta, side) = if wo.z() > 0.0 {
+            (wo, self.reflection_index, 1.0)
+        } else {
+            (-wo, 1.0 / self.reflection_index, -1.0)
+        };
+
+        let (u1, u2) = sampler.get_2d();
+        let m = self.distribution.sample_visible_normal(wo, u1, u2);
+        let fresnel = fresnel_dielectric(wo.dot(&m), eta);
+
+        // Reflection is picked with probability F and transmission with 1 - F.
+        // So, The pdfs are
+        //     reflection:   F * D_wo(m) / (4 * |wo.m|)
+        //     transmission: (1 - F) * D_wo(m) * |wi.m| / (wi.m + wo.m / eta)^2
+        // where D_wo is the distribution of visible normals. Dividing Walter's BRDF and BTDF
+        // times |cos(wi)| by those, Everything except G2 / G1 cancels out in both the cases
+        let (wi, attenuation) = if sampler.get_1d() < fresnel {
+            let wi = reflect(-wo, m);
+            if wi.z() <= 0.0 {
+                return (Vec3::new(0.0, 0.0, 0.0), None);
+            }
+            (wi, Vec3::new(1.0, 1.0, 1.0))
+        } else {
+            match refract_through(wo, m, eta) {
+                Some(wi) if wi.z() < 0.0 => (wi, self.tint),
+                _ => return (Vec3::new(0.0, 0.0, 0.0), None),
+            }
+        };
+
+        let weight = self.distribution.g2(wo, wi) / self.distribution.g1(wo);
+
+        (
+            attenuation * weight,
+            Some(Ray::new(hit_rec.p, frame.to_world(wi * side))),
+        )
+    }
+}
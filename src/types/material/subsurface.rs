@@ -0,0 +1,124 @@
+use {
+    super::{microfacet::fresnel_dielectric, reflect, refract},
+    crate::types::{HitRecord, Material, Ray, Sampler, Vec3},
+    std::f64::consts::PI,
+};
+
+/// Translucent material for skin, wax, marble, milk etc.
+///
+/// Light refracts into the object and does a random walk through the volume enclosed
+/// by the object until it leaves through the surface. This works because any ray that's
+/// traveling inside a closed object hits the boundary of that same object next.
+/// So, Every time we are called with a ray from the inside, We sample a distance along it and
+/// either scatter at that point in the volume or refract out if the boundary was closer.
+pub struct Subsurface {
+    reflection_index: f64,
+    /// Extinction coefficient, 1 / mean free path. Per RGB channel
+    sigma_t: Vec3,
+    /// Single scattering albedo, Fraction of light that's scattered instead of absorbed
+    /// at each interaction. Per RGB channel
+    albedo: Vec3,
+}
+
+impl Subsurface {
+    /// `mean_free_path` is the average distance light travels inside the object before
+    /// interacting with it, In world units
+    pub fn new(reflection_index: f64, mean_free_path: Vec3, albedo: Vec3) -> Self {
+        Self {
+            reflection_index,
+            sigma_t: Vec3::new(
+                1.0 / mean_free_path.r(),
+                1.0 / mean_free_path.g(),
+                1.0 / mean_free_path.b(),
+            ),
+            albedo,
+        }
+    }
+
+    fn transmittance(&self, distance: f64) -> Vec3 {
+        Vec3::new(
+            (-self.sigma_t.r() * distance).exp(),
+            (-self.sigma_t.g() * distance).exp(),
+            (-self.sigma_t.b() * distance).exp(),
+        )
+    }
+
+    /// Ray arrived at the surface from the outside
+    fn enter(&self, ray_in: &Ray, hit_rec: &HitRecord, sampler: &mut dyn Sampler) -> Ray {
+        let direction = ray_in.direction().unit_vector();
+        let cosine = -direction.dot(&hit_rec.normal);
+        let ni_over_nt = 1.0 / self.reflection_index;
+
+        match refract(direction, hit_rec.normal, ni_over_nt) {
+            Some(refracted)
+                if sampler.get_1d() >= fresnel_dielectric(cosine, self.reflection_index) =>
+            {
+                Ray::new(hit_rec.p, refracted)
+            }
+            _ => Ray::new(hit_rec.p, reflect(direction, hit_rec.normal)),
+        }
+    }
+
+    /// Ray is traveling through the volume and the boundary is `hit_rec.t` away
+    fn walk(&self, ray_in: &Ray, hit_rec: &HitRecord, sampler: &mut dyn Sampler) -> (Vec3, Ray) {
+        let direction = ray_in.direction().unit_vector();
+        let boundary = hit_rec.t * ray_in.direction().length();
+
+        // Each channel has it's own extinction coefficient. Pick one of them to sample
+        // the distance and weigh the result by the average pdf of all three channels
+        let channel = ((sampler.get_1d() * 3.0) as usize).min(2);
+        let distance = -(1.0 - sampler.get_1d()).ln() / self.sigma_t[channel];
+
+        if distance < boundary {
+            // Scatter inside the volume, uniformly in all directions
+            let transmittance = self.transmittance(distance);
+            let pdf = (self.sigma_t * transmittance).dot(&Vec3::new(1.0, 1.0, 1.0)) / 3.0;
+
+            let z = 1.0 - 2.0 * sampler.get_1d();
+            let r = (1.0 - z * z).max(0.0).sqrt();
+            let phi = 2.0 * PI * sampler.get_1d();
+            let scattered = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+
+            (
+                self.albedo * self.sigma_t * transmittance / pdf,
+                Ray::new(ray_in.origin() + direction * distance, scattered),
+            )
+        } else {
+            // Made it to the boundary. Either refract out or get reflected back in
+            let transmittance = self.transmittance(boundary);
+            let pdf = transmittance.dot(&Vec3::new(1.0, 1.0, 1.0)) / 3.0;
+            let cosine = direction.dot(&hit_rec.normal);
+
+            let scattered = match refract(direction, -hit_rec.normal, self.reflection_index) {
+                Some(refracted)
+                    if sampler.get_1d()
+                        >= fresnel_dielectric(cosine, 1.0 / self.reflection_index) =>
+                {
+                    refracted
+                }
+                _ => reflect(direction, -hit_rec.normal),
+            };
+
+            (transmittance / pdf, Ray::new(hit_rec.p, scattered))
+        }
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        if ray_in.direction().dot(&hit_rec.normal) < 0.0 {
+            (
+                Vec3::new(1.0, 1.0, 1.0),
+                Some(self.enter(ray_in, hit_rec, sampler)),
+            )
+        } else {
+            let (attenuation, scattered) = self.walk(ray_in, hit_rec, sampler);
+            (attenuation, Some(scattered))
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use {
+    crate::types::{HitRecord, Texture, Vec3},
+    std::{
+        f64::consts::PI,
+        ops::{Add, Div, Mul, Sub},
+    },
+};
+
+// Wavelengths(in nm) averaged together for each of the R, G and B channels.
+// A single wavelength per channel gives harsh, oversaturated bands
+const WAVELENGTHS: [[f64; 5]; 3] = [
+    [610.0, 630.0, 650.0, 670.0, 690.0],
+    [510.0, 530.0, 550.0, 570.0, 590.0],
+    [410.0, 430.0, 450.0, 470.0, 490.0],
+];
+
+/// A very thin layer of a transparent material(soap, oil, oxides on metal) on top of a surface.
+/// Light reflected from the top and the bottom of the film interferes, Which makes the
+/// reflectance depend on the wavelength, The viewing angle and the thickness of the film.
+///
+/// See "A Practical Extension to Microfacet Theory for the Modeling of Varying Iridescence"
+/// by Belcour and Barla
+pub struct ThinFilm {
+    reflection_index: f64,
+    /// in nanometers
+    thickness: f64,
+    /// Scales `thickness` over the surface using the red channel of the texture
+    thickness_texture: Option<Box<dyn Texture>>,
+}
+
+impl ThinFilm {
+    pub fn new(reflection_index: f64, thickness: f64) -> Self {
+        Self {
+            reflection_index,
+            thickness,
+            thickness_texture: None,
+        }
+    }
+
+    pub fn thickness_texture(mut self, texture: Box<dyn Texture>) -> Self {
+        self.thickness_texture = Some(texture);
+        self
+    }
+
+    /// Reflectance of the film in air sitting on top of a substrate with
+    /// complex index of refraction eta + i * k, per RGB channel.
+    /// Dielectric substrates have k = 0
+    pub fn reflectance(&self, cos_i: f64, hit_rec: &HitRecord, eta: Vec3, k: Vec3) -> Vec3 {
+        let thickness = match &self.thickness_texture {
+            Some(texture) => self.thickness * texture.value(hit_rec.u, hit_rec.v, hit_rec.p).r(),
+            None => self.thickness,
+        };
+
+        let mut reflectance = Vec3::new(0.0, 0.0, 0.0);
+        for channel in 0..3 {
+            let substrate = Complex::new(eta[channel], k[channel]);
+            for &wavelength in WAVELENGTHS[channel].iter() {
+                reflectance[channel] +=
+                    self.airy_reflectance(cos_i, thickness, wavelength, substrate);
+            }
+            reflectance[channel] /= WAVELENGTHS[channel].len() as f64;
+        }
+        reflectance
+    }
+
+    /// Sums up all the light bouncing between the two boundaries of the film for one wavelength
+    fn airy_reflectance(&self, cos_i: f64, thickness: f64, wavelength: f64, n3: Complex) -> f64 {
+        let cos_i = cos_i.clamp(0.0, 1.0);
+        let sin2_i = Complex::real(1.0 - cos_i * cos_i);
+        let n1 = Complex::real(1.0);
+        let n2 = Complex::real(self.reflection_index);
+
+        // Snell's law, sin(theta_t) = n_i * sin(theta_i) / n_t
+        let cos1 = Complex::real(cos_i);
+        let cos2 = (Complex::real(1.0) - sin2_i / (n2 * n2)).sqrt();
+        let cos3 = (Complex::real(1.0) - sin2_i / (n3 * n3)).sqrt();
+
+        // Phase difference between light reflected off the top and the bottom of the film
+        let delta = 4.0 * PI * self.reflection_index * thickness * cos2.re / wavelength;
+        let phase = Complex::new(delta.cos(), delta.sin());
+
+        let airy = |r12: Complex, r23: Complex| {
+            let r = (r12 + r23 * phase) / (Complex::real(1.0) + r12 * r23 * phase);
+            r.norm_sqr()
+        };
+
+        let rs = airy(fresnel_s(n1, cos1, n2, cos2), fresnel_s(n2, cos2, n3, cos3));
+        let rp = airy(fresnel_p(n1, cos1, n2, cos2), fresnel_p(n2, cos2, n3, cos3));
+
+        (0.5 * (rs + rp)).min(1.0)
+    }
+}
+
+// Fresnel amplitude coefficients for s and p polarized light
+fn fresnel_s(ni: Complex, cos_i: Complex, nt: Complex, cos_t: Complex) -> Complex {
+    (ni * cos_i - nt * cos_t) / (ni * cos_i + nt * cos_t)
+}
+
+fn fresnel_p(ni: Complex, cos_i: Complex, nt: Complex, cos_t: Complex) -> Complex {
+    (nt * cos_i - ni * cos_t) / (nt * cos_i + ni * cos_t)
+}
+
+#[derive(Copy, Clone)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+    fn real(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+    fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+    // principal square root
+    fn sqrt(self) -> Self {
+        let norm = self.norm_sqr().sqrt();
+        let re = ((norm + self.re) * 0.5).max(0.0).sqrt();
+        let im = ((norm - self.re) * 0.5).max(0.0).sqrt();
+        Self::new(re, im.copysign(self.im))
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, o: Complex) -> Complex {
+        Complex::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, o: Complex) -> Complex {
+        Complex::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, o: Complex) -> Complex {
+        Complex::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, o: Complex) -> Complex {
+        let d = o.norm_sqr();
+        Complex::new(
+            (self.re * o.re + self.im * o.im) / d,
+            (self.im * o.re - self.re * o.im) / d,
+        )
+    }
+}
@@ -0,0 +1,76 @@
+use crate::types::{HitRecord, Material, Ray, Sampler, Vec3};
+
+/// Cel shading for illustrations and diagrams.
+/// Lighting from a single directional light is quantised into flat bands and
+/// a rim light outlines the edges facing away from the camera.
+///
+/// The shading is emitted and rays are never scattered, So toon surfaces don't receive
+/// shadows or reflect other objects and look the same under any environment.
+pub struct Toon {
+    color: Vec3,
+    /// unit vector pointing towards the light
+    light: Vec3,
+    bands: u32,
+    /// brightness of the darkest band, relative to the lit color
+    shadow: f64,
+    rim_color: Vec3,
+    /// fraction of the silhouette covered by the rim, 0 turns it off
+    rim_width: f64,
+}
+
+impl Toon {
+    pub fn new(color: Vec3) -> Self {
+        Self {
+            color,
+            light: Vec3::new(1.0, 1.0, 1.0).unit_vector(),
+            bands: 3,
+            shadow: 0.3,
+            rim_color: Vec3::new(1.0, 1.0, 1.0),
+            rim_width: 0.0,
+        }
+    }
+
+    /// Direction the light comes from
+    pub fn light(mut self, direction: Vec3) -> Self {
+        self.light = direction.unit_vector();
+        self
+    }
+    pub fn bands(mut self, bands: u32) -> Self {
+        self.bands = bands.max(1);
+        self
+    }
+    pub fn shadow(mut self, shadow: f64) -> Self {
+        self.shadow = shadow;
+        self
+    }
+    pub fn rim(mut self, color: Vec3, width: f64) -> Self {
+        self.rim_color = color;
+        self.rim_width = width;
+        self
+    }
+}
+
+impl Material for Toon {
+    fn scatter(
+        &self,
+        _ray_in: &Ray,
+        _hit_rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> (Vec3, Option<Ray>) {
+        (Vec3::new(0.0, 0.0, 0.0), None)
+    }
+
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        let n_dot_l = hit_rec.normal.dot(&self.light).max(0.0);
+        // 0 for the unlit side, then one step for every band
+        let band = (n_dot_l * self.bands as f64).ceil() / self.bands as f64;
+        let lit = self.color * (self.shadow + (1.0 - self.shadow) * band);
+
+        let n_dot_v = -ray_in.direction().unit_vector().dot(&hit_rec.normal);
+        if n_dot_l > 0.0 && n_dot_v.abs() < self.rim_width {
+            self.rim_color
+        } else {
+            lit
+        }
+    }
+}
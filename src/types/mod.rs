@@ -1,13 +1,30 @@
+mod heterogeneous_medium;
 mod hitable;
 mod hitable_list;
+pub mod light;
 pub mod material;
+mod normal_mapping;
+mod onb;
 mod ray;
+mod rectangle;
+pub mod sampler;
+pub mod spectrum;
 mod sphere;
+pub mod texture;
 mod vec3;
+mod voxel_grid;
 
+pub use heterogeneous_medium::HeterogeneousMedium;
 pub use hitable::{HitRecord, Hitable};
 pub use hitable_list::HitableList;
+pub use light::Light;
 pub use material::Material;
+pub use normal_mapping::{BumpMapped, NormalMapped};
+pub use onb::Onb;
 pub use ray::Ray;
+pub use rectangle::Rectangle;
+pub use sampler::Sampler;
 pub use sphere::Sphere;
+pub use texture::Texture;
 pub use vec3::Vec3;
+pub use voxel_grid::VoxelGrid;
@@ -0,0 +1,94 @@
+use crate::types::{HitRecord, Hitable, Ray, Texture, Vec3};
+
+/// Adds surface detail to any object by bending the normals using a tangent space normal map.
+/// Each pixel of the map is a normal with x, y, z in -1..1 range stored as RGB in 0..1 range.
+/// x follows the tangent, y the bitangent and z the original normal
+pub struct NormalMapped {
+    object: Box<dyn Hitable>,
+    map: Box<dyn Texture>,
+}
+
+impl NormalMapped {
+    pub fn new(object: Box<dyn Hitable>, map: Box<dyn Texture>) -> Self {
+        Self { object, map }
+    }
+}
+
+impl Hitable for NormalMapped {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let mut hit_rec = self.object.hit(ray, t_min, t_max)?;
+        let local =
+            self.map.value(hit_rec.u, hit_rec.v, hit_rec.p) * 2.0 - Vec3::new(1.0, 1.0, 1.0);
+
+        let bitangent = hit_rec.normal.cross(&hit_rec.tangent);
+        let normal =
+            hit_rec.tangent * local.x() + bitangent * local.y() + hit_rec.normal * local.z();
+
+        set_shading_normal(&mut hit_rec, normal.unit_vector());
+        Some(hit_rec)
+    }
+}
+
+/// Adds surface detail to any object by bending the normals as if the surface was
+/// displaced along the normal by a height map. Only the red channel of the height map is used.
+///
+/// The slope of the height map is found by sampling it slightly away from the hit point
+/// along the tangent frame. Both the point and the surface coordinates are offset, So it
+/// works with solid textures like noise and with height map images alike
+pub struct BumpMapped {
+    object: Box<dyn Hitable>,
+    height: Box<dyn Texture>,
+    /// How far the surface is displaced for a height of 1.0
+    scale: f64,
+}
+
+impl BumpMapped {
+    pub fn new(object: Box<dyn Hitable>, height: Box<dyn Texture>, scale: f64) -> Self {
+        Self {
+            object,
+            height,
+            scale,
+        }
+    }
+}
+
+impl Hitable for BumpMapped {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        const DELTA: f64 = 1e-3;
+
+        let mut hit_rec = self.object.hit(ray, t_min, t_max)?;
+        // Change in u and v that moves p by `offset`, dp/du and dp/dv are perpendicular
+        // on all the shapes so each one can be projected on separately
+        let along = |offset: Vec3, dp: Vec3| {
+            if dp.sq_len() < 1e-12 {
+                0.0
+            } else {
+                offset.dot(&dp) / dp.sq_len()
+            }
+        };
+        let height = |offset: Vec3| {
+            let u = hit_rec.u + along(offset, hit_rec.dpdu);
+            let v = hit_rec.v + along(offset, hit_rec.dpdv);
+            self.height.value(u, v, hit_rec.p + offset).r() * self.scale
+        };
+        let bitangent = hit_rec.normal.cross(&hit_rec.tangent);
+
+        let h = height(Vec3::new(0.0, 0.0, 0.0));
+        let dh_dt = (height(hit_rec.tangent * DELTA) - h) / DELTA;
+        let dh_db = (height(bitangent * DELTA) - h) / DELTA;
+
+        let normal = hit_rec.normal - hit_rec.tangent * dh_dt - bitangent * dh_db;
+
+        set_shading_normal(&mut hit_rec, normal.unit_vector());
+        Some(hit_rec)
+    }
+}
+
+// Replaces the normal and makes the tangent perpendicular to it again(Gram-Schmidt)
+fn set_shading_normal(hit_rec: &mut HitRecord, normal: Vec3) {
+    let tangent = hit_rec.tangent - normal * normal.dot(&hit_rec.tangent);
+    hit_rec.normal = normal;
+    if tangent.sq_len() > 1e-12 {
+        hit_rec.tangent = tangent.unit_vector();
+    }
+}
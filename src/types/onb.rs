@@ -0,0 +1,54 @@
+use crate::types::Vec3;
+
+/// Orthonormal basis built around a surface normal.
+/// Lets materials work in a local shading frame where the normal is the +z axis
+pub struct Onb {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Onb {
+    /// Builds a frame with `w` as the z axis. `w` must be a unit vector
+    ///
+    /// Uses the branchless construction from
+    /// "Building an Orthonormal Basis, Revisited" by Duff et al.
+    pub fn from_w(w: Vec3) -> Self {
+        let sign = 1.0f64.copysign(w.z());
+        let a = -1.0 / (sign + w.z());
+        let b = w.x() * w.y() * a;
+
+        let u = Vec3::new(1.0 + sign * w.x() * w.x() * a, sign * b, -sign * w.x());
+        let v = Vec3::new(b, sign + w.y() * w.y() * a, -w.y());
+
+        Self { u, v, w }
+    }
+
+    /// Builds a frame with `w` as the z axis and `u` as the x axis.
+    /// `u` is made perpendicular to `w` first. Falls back to `from_w` if they are parallel
+    pub fn from_w_u(w: Vec3, u: Vec3) -> Self {
+        let u = u - w * w.dot(&u);
+        if u.sq_len() < 1e-12 {
+            return Self::from_w(w);
+        }
+        let u = u.unit_vector();
+
+        Self {
+            u,
+            v: w.cross(&u),
+            w,
+        }
+    }
+
+    /// Converts a vector from world space to this frame
+    #[inline]
+    pub fn to_local(&self, a: Vec3) -> Vec3 {
+        Vec3::new(a.dot(&self.u), a.dot(&self.v), a.dot(&self.w))
+    }
+
+    /// Converts a vector in this frame back to world space
+    #[inline]
+    pub fn to_world(&self, a: Vec3) -> Vec3 {
+        self.u * a.x() + self.v * a.y() + self.w * a.z()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::types::{HitRecord, Hitable, Material, Ray, Vec3};
+
+/// Flat rectangle with one corner at `corner` and sides along `u` and `v`.
+/// `u` and `v` should be perpendicular, Otherwise it's a parallelogram
+pub struct Rectangle {
+    corner: Vec3,
+    u: Vec3,
+    v: Vec3,
+    /// unit normal of the plane the rectangle is in
+    normal: Vec3,
+    /// (u x v) / |u x v|^2, used to get the surface coordinates of a point on the plane
+    w: Vec3,
+    material: Option<Box<dyn Material>>,
+}
+
+impl Rectangle {
+    pub fn new(corner: Vec3, u: Vec3, v: Vec3) -> Self {
+        let n = u.cross(&v);
+        Self {
+            corner,
+            u,
+            v,
+            normal: n.unit_vector(),
+            w: n / n.sq_len(),
+            material: None,
+        }
+    }
+    pub fn with_material(corner: Vec3, u: Vec3, v: Vec3, material: Box<dyn Material>) -> Self {
+        Self {
+            material: Some(material),
+            ..Self::new(corner, u, v)
+        }
+    }
+}
+
+impl Hitable for Rectangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let denominator = self.normal.dot(&ray.direction());
+        // Ray is parallel to the plane
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.corner - ray.origin()).dot(&self.normal) / denominator;
+        if t <= t_min || t >= t_max {
+            return None;
+        }
+
+        let p = ray.point_at_parameter(t);
+        let planar = p - self.corner;
+        let alpha = self.w.dot(&planar.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        // A rectangle has no inside so the normal always faces the incoming ray
+        let normal = if denominator < 0.0 {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        Some(HitRecord {
+            t,
+            p,
+            normal,
+            tangent: self.u.unit_vector(),
+            u: alpha,
+            v: beta,
+            dpdu: self.u,
+            dpdv: self.v,
+            material: self.material.as_ref(),
+        })
+    }
+}
@@ -0,0 +1,316 @@
+mod halton;
+mod random;
+mod sobol;
+
+pub use halton::HaltonSampler;
+pub use random::{random, SeededRng};
+pub use sobol::SobolSampler;
+
+use {rand::Rng, random::reseed_thread_rng};
+
+/// Number of dimensions reserved for each bounce when a sampler needs to know how many
+/// dimensions there are, Like the bases of `HaltonSampler`. Bounces can use more
+const BOUNCE_DIMENSIONS: usize = 8;
+
+/// The camera takes the pixel offset, the lens and the time. There's no shutter yet so
+/// time is left unused, But bounces still start after it
+const CAMERA_DIMENSIONS: usize = 3;
+
+/// Hands out the random numbers used to build a path, One dimension at a time.
+/// Every decision takes the next dimension of the current pixel sample, So the pixel
+/// offset, the lens and each bounce always get the same dimensions and samplers can
+/// spread them out across the samples of a pixel.
+///
+/// Samples only depend on the seed, the pixel and the sample index. Rendering with the
+/// same seed gives exactly the same image no matter how the work is split between threads
+pub trait Sampler {
+    /// Starts the `index`th sample of pixel (x, y), Going back to the camera's dimensions.
+    /// Also reseeds `random` for the rest of the renderer
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize);
+
+    /// Moves on to the dimensions of `bounce`. Bounces always get their own dimensions,
+    /// No matter how many the camera and earlier bounces used
+    fn start_bounce(&mut self, bounce: usize);
+
+    /// Next dimension, in 0..1 range
+    fn get_1d(&mut self) -> f64;
+
+    /// Next two dimensions, For things like the pixel offset and points on the lens
+    fn get_2d(&mut self) -> (f64, f64);
+}
+
+/// Samplers that can be picked when rendering a demo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplerKind {
+    Independent,
+    /// Stratified with every sample at the center of its stratum
+    Stratified,
+    /// Stratified with samples jittered inside their stratum
+    Jittered,
+    Sobol,
+    Halton,
+}
+
+impl SamplerKind {
+    /// Sampler for rendering `samples` samples per pixel
+    pub fn create(self, samples: usize, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            SamplerKind::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerKind::Stratified => {
+                Box::new(StratifiedSampler::new(samples, seed).jitter(false))
+            }
+            SamplerKind::Jittered => Box::new(StratifiedSampler::new(samples, seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(seed)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            SamplerKind::Independent => SamplerKind::Stratified,
+            SamplerKind::Stratified => SamplerKind::Jittered,
+            SamplerKind::Jittered => SamplerKind::Sobol,
+            SamplerKind::Sobol => SamplerKind::Halton,
+            SamplerKind::Halton => SamplerKind::Independent,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SamplerKind::Independent => "independent",
+            SamplerKind::Stratified => "stratified",
+            SamplerKind::Jittered => "jittered",
+            SamplerKind::Sobol => "sobol",
+            SamplerKind::Halton => "halton",
+        }
+    }
+}
+
+/// Uniform random numbers with nothing tying the samples of a pixel together.
+/// Samples can clump up and leave holes, Which shows up as white noise
+pub struct IndependentSampler {
+    position: Position,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            position: Position::new(seed),
+        }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.position.start_pixel_sample(x, y, index);
+    }
+
+    fn start_bounce(&mut self, _bounce: usize) {}
+
+    fn get_1d(&mut self) -> f64 {
+        self.position.rng.gen::<f64>()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (
+            self.position.rng.gen::<f64>(),
+            self.position.rng.gen::<f64>(),
+        )
+    }
+}
+
+/// Splits every dimension into as many strata as there are samples and puts one sample
+/// in each, 2D samples use a grid of strata. Samples are jittered inside their stratum
+/// unless jitter is turned off.
+/// Which stratum a sample lands in is shuffled separately for every pixel and dimension,
+/// Otherwise the lens sample would always follow the pixel offset
+pub struct StratifiedSampler {
+    samples: usize,
+    x_strata: usize,
+    y_strata: usize,
+    jitter: bool,
+    position: Position,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples: usize, seed: u64) -> Self {
+        let samples = samples.max(1);
+        // As square a grid as possible that still has a stratum for every sample
+        let x_strata = ((samples as f64).sqrt().round() as usize).max(1);
+        let y_strata = samples.div_ceil(x_strata);
+
+        Self {
+            samples,
+            x_strata,
+            y_strata,
+            jitter: true,
+            position: Position::new(seed),
+        }
+    }
+
+    /// Without jitter, Samples sit at the center of their strata
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stratum the current sample falls in for the next dimension, Out of `count` strata
+    fn stratum(&mut self, count: usize) -> usize {
+        // Once a pixel has more samples than strata, Every round gets a new shuffle
+        let index = self.position.index;
+        let seed = hash(&[self.position.next().seed, (index / count) as u64]);
+
+        permutation_element((index % count) as u32, count as u32, seed as u32) as usize
+    }
+
+    fn offset(&mut self) -> f64 {
+        if self.jitter {
+            self.position.rng.gen::<f64>()
+        } else {
+            0.5
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.position.start_pixel_sample(x, y, index);
+    }
+
+    fn start_bounce(&mut self, bounce: usize) {
+        self.position.start_bounce(bounce);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let stratum = self.stratum(self.samples);
+        (stratum as f64 + self.offset()) / self.samples as f64
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let stratum = self.stratum(self.x_strata * self.y_strata);
+        let (x, y) = (stratum % self.x_strata, stratum / self.x_strata);
+        (
+            (x as f64 + self.offset()) / self.x_strata as f64,
+            (y as f64 + self.offset()) / self.y_strata as f64,
+        )
+    }
+}
+
+/// Where a sampler is in the dimensions of the current pixel sample
+struct Position {
+    seed: u64,
+    /// Seeded from the pixel sample, For the parts of samplers that are just random
+    rng: SeededRng,
+    pixel: (usize, usize),
+    index: usize,
+    /// 0 for the camera and bounce + 1 after that
+    part: usize,
+    dimension: usize,
+}
+
+struct Dimension {
+    /// Dimensions numbered one after the other, Bounces that use more than
+    /// `BOUNCE_DIMENSIONS` overlap with the next one
+    number: usize,
+    /// Different for every pixel and dimension, Even where `number` overlaps
+    seed: u64,
+}
+
+impl Position {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: SeededRng::new(seed),
+            pixel: (0, 0),
+            index: 0,
+            part: 0,
+            dimension: 0,
+        }
+    }
+
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        let seed = hash(&[self.seed, x as u64, y as u64, index as u64]);
+        self.rng = SeededRng::new(seed);
+        reseed_thread_rng(mix_bits(seed));
+
+        self.pixel = (x, y);
+        self.index = index;
+        self.part = 0;
+        self.dimension = 0;
+    }
+
+    fn start_bounce(&mut self, bounce: usize) {
+        self.part = bounce + 1;
+        self.dimension = 0;
+    }
+
+    fn next(&mut self) -> Dimension {
+        let number = match self.part {
+            0 => self.dimension,
+            part => CAMERA_DIMENSIONS + (part - 1) * BOUNCE_DIMENSIONS + self.dimension,
+        };
+        let seed = hash(&[
+            self.seed,
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            self.part as u64,
+            self.dimension as u64,
+        ]);
+        self.dimension += 1;
+
+        Dimension { number, seed }
+    }
+}
+
+/// Scrambles the bits of `v` so nearby inputs give unrelated outputs
+fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5_d329_728e_a185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81da_def4_bc2d_d44d);
+    v ^= v >> 33;
+    v
+}
+
+fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0, |hash, &value| {
+        mix_bits(hash ^ value.wrapping_add(0x9e37_79b9_7f4a_7c15))
+    })
+}
+
+/// `i`th element of a random permutation of 0..`count` picked by `seed`, Without having
+/// to build the permutation. See Andrew Kensler, Correlated Multi-Jittered Sampling
+fn permutation_element(mut i: u32, count: u32, seed: u32) -> u32 {
+    let mut mask = count.wrapping_sub(1);
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+
+    // Shuffles within the next power of two and retries until the result fits in count
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & mask) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & mask) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & mask) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & mask) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & mask) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= mask;
+        i ^= i >> 5;
+        if i < count {
+            break;
+        }
+    }
+    i.wrapping_add(seed) % count
+}
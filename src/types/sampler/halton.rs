@@ -0,0 +1,91 @@
+use super::{permutation_element, Position, Sampler};
+
+/// Number of prime bases, Dimensions past this reuse bases with different permutations
+const BASES: usize = 256;
+
+/// The Halton sequence, Where every dimension uses the digits of the sample index in a
+/// different prime base reversed around the decimal point.
+///
+/// The digits are shuffled with a random permutation for every pixel and dimension.
+/// Without that, The high dimensions with large bases line up in long correlated
+/// diagonals and every pixel would get exactly the same points
+pub struct HaltonSampler {
+    primes: Vec<u32>,
+    position: Position,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            primes: primes(BASES),
+            position: Position::new(seed),
+        }
+    }
+
+    /// Sample from the next dimension, 2D samples take two bases in a row
+    fn next(&mut self, dimensions: usize) -> impl Iterator<Item = f64> + '_ {
+        let index = self.position.index as u64;
+        let dimension = self.position.next();
+        let primes = &self.primes;
+
+        (0..dimensions).map(move |i| {
+            let base = primes[(dimension.number * 2 + i) % primes.len()];
+            scrambled_radical_inverse(base, index, (dimension.seed >> (32 * i)) as u32)
+        })
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.position.start_pixel_sample(x, y, index);
+    }
+
+    fn start_bounce(&mut self, bounce: usize) {
+        self.position.start_bounce(bounce);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        self.next(1).next().unwrap()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let mut samples = self.next(2);
+        (samples.next().unwrap(), samples.next().unwrap())
+    }
+}
+
+/// Mirrors the digits of `index` in `base` around the decimal point, Putting every digit
+/// through the same permutation picked by `seed`
+fn scrambled_radical_inverse(base: u32, mut index: u64, seed: u32) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut reversed = 0;
+    let mut inv_base_n = 1.0;
+    while index > 0 {
+        let digit = (index % base as u64) as u32;
+        reversed = reversed * base as u64 + permutation_element(digit, base, seed) as u64;
+        inv_base_n *= inv_base;
+        index /= base as u64;
+    }
+
+    // Past the last digit, The index is padded with infinitely many zeros. After the
+    // permutation they're not zero anymore and add up to a geometric series
+    let padding = permutation_element(0, base, seed) as f64 * inv_base / (1.0 - inv_base);
+    (inv_base_n * (reversed as f64 + padding)).min(1.0 - f64::EPSILON)
+}
+
+/// First `count` prime numbers
+fn primes(count: usize) -> Vec<u32> {
+    let mut primes: Vec<u32> = Vec::with_capacity(count);
+    let mut n = 2;
+    while primes.len() < count {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= n)
+            .all(|&p| n % p != 0)
+        {
+            primes.push(n);
+        }
+        n += 1;
+    }
+    primes
+}
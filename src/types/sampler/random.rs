@@ -0,0 +1,54 @@
+use {
+    rand::{Error, Rng, RngCore},
+    std::cell::RefCell,
+};
+
+/// SplitMix64. Small, Fast and cheap enough to seed that every pixel sample gets its own
+/// generator, So the numbers don't depend on which thread rendered what before
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        super::mix_bits(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<SeededRng> = RefCell::new(SeededRng::new(0));
+}
+
+/// Random number in 0..1 range for the parts of the renderer that don't get a sampler,
+/// Like hitting transparent surfaces, Tracking through media and picking lights.
+/// Samplers reseed it at the start of every pixel sample on the thread rendering it
+pub fn random() -> f64 {
+    THREAD_RNG.with(|rng| rng.borrow_mut().gen::<f64>())
+}
+
+pub(super) fn reseed_thread_rng(seed: u64) {
+    THREAD_RNG.with(|rng| *rng.borrow_mut() = SeededRng::new(seed));
+}
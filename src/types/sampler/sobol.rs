@@ -0,0 +1,97 @@
+use super::{hash, mix_bits, Position, Sampler};
+
+/// The first two dimensions of the Sobol sequence with Owen scrambling.
+///
+/// Every 1D or 2D sample takes points from the same two dimensions, But the order of the
+/// points is shuffled differently for every pixel and dimension so the dimensions
+/// aren't correlated with each other. Each pixel also scrambles the points themselves,
+/// Which keeps them well spread out while removing the structure between pixels.
+///
+/// See "Practical Hash-based Owen Scrambling" by Brent Burley,
+/// http://www.jcgt.org/published/0009/04/01/
+pub struct SobolSampler {
+    position: Position,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            position: Position::new(seed),
+        }
+    }
+
+    /// Index of the point the current sample uses, Shuffled with `seed`
+    fn shuffled_index(&self, seed: u64) -> u32 {
+        nested_uniform_scramble(self.position.index as u32, mix_bits(seed) as u32)
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, x: usize, y: usize, index: usize) {
+        self.position.start_pixel_sample(x, y, index);
+    }
+
+    fn start_bounce(&mut self, bounce: usize) {
+        self.position.start_bounce(bounce);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let seed = self.position.next().seed;
+        let index = self.shuffled_index(seed);
+
+        to_unit(nested_uniform_scramble(
+            index.reverse_bits(),
+            hash(&[seed, 0]) as u32,
+        ))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let seed = self.position.next().seed;
+        let index = self.shuffled_index(seed);
+
+        (
+            to_unit(nested_uniform_scramble(
+                index.reverse_bits(),
+                hash(&[seed, 0]) as u32,
+            )),
+            to_unit(nested_uniform_scramble(
+                sobol_second_dimension(index),
+                hash(&[seed, 1]) as u32,
+            )),
+        )
+    }
+}
+
+/// The first dimension of the sequence is just the bits of the index in reverse.
+/// The second one xors together a direction number for every set bit of the index
+fn sobol_second_dimension(index: u32) -> u32 {
+    let mut direction = 1 << 31;
+    let mut value = 0;
+    for bit in 0..32 {
+        if index & (1 << bit) != 0 {
+            value ^= direction;
+        }
+        direction ^= direction >> 1;
+    }
+    value
+}
+
+/// Owen scrambling. Randomly flips the bits of `x` where each flip only depends on the
+/// bits above it, So points stay in the same intervals relative to each other
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// Hash where each bit only depends on the bits below it
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+fn to_unit(x: u32) -> f64 {
+    x as f64 / (1u64 << 32) as f64
+}
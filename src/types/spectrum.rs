@@ -0,0 +1,65 @@
+//! Conversions from spectral quantities to the linear sRGB colors used everywhere else
+
+use crate::types::Vec3;
+
+/// Luminous efficacy at 555nm. Converts photometric units(nits, candela) to the units used
+/// for light in the scene, Where a radiance of 1.0 has a luminance of this many nits
+pub const LUMENS_PER_WATT: f64 = 683.0;
+
+/// Color of an ideal blackbody radiator at `kelvin` in linear sRGB,
+/// Normalised so it has a luminance of 1.
+///
+/// Colors below ~1900K and above ~10000K fall out of the sRGB gamut and are clamped
+pub fn blackbody(kelvin: f64) -> Vec3 {
+    let mut xyz = Vec3::new(0.0, 0.0, 0.0);
+    let mut wavelength = 380.0;
+    while wavelength <= 780.0 {
+        xyz += cie_xyz(wavelength) * planck(wavelength, kelvin);
+        wavelength += 5.0;
+    }
+
+    let rgb = xyz_to_srgb(xyz / xyz.y());
+    let rgb = Vec3::new(rgb.r().max(0.0), rgb.g().max(0.0), rgb.b().max(0.0));
+    rgb / luminance(rgb)
+}
+
+/// Relative luminance of a linear sRGB color
+pub fn luminance(color: Vec3) -> f64 {
+    color.dot(&Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+/// Spectral radiance of a blackbody from Planck's law. `wavelength` is in nanometers
+fn planck(wavelength: f64, kelvin: f64) -> f64 {
+    const H: f64 = 6.626_070_15e-34;
+    const C: f64 = 299_792_458.0;
+    const K: f64 = 1.380_649e-23;
+
+    let l = wavelength * 1e-9;
+    2.0 * H * C * C / (l.powi(5) * ((H * C / (l * K * kelvin)).exp() - 1.0))
+}
+
+/// CIE 1931 2° color matching functions.
+///
+/// Uses the multi lobe fit from "Simple Analytic Approximations to the CIE XYZ
+/// Color Matching Functions" by Wyman, Sloan and Shirley
+fn cie_xyz(wavelength: f64) -> Vec3 {
+    let g = |mu: f64, sigma1: f64, sigma2: f64| {
+        let t = (wavelength - mu) / if wavelength < mu { sigma1 } else { sigma2 };
+        (-0.5 * t * t).exp()
+    };
+
+    Vec3::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+// D65 white point
+fn xyz_to_srgb(xyz: Vec3) -> Vec3 {
+    Vec3::new(
+        3.2406 * xyz.x() - 1.5372 * xyz.y() - 0.4986 * xyz.z(),
+        -0.9689 * xyz.x() + 1.8758 * xyz.y() + 0.0415 * xyz.z(),
+        0.0557 * xyz.x() - 0.2040 * xyz.y() + 1.0570 * xyz.z(),
+    )
+}
@@ -21,6 +21,46 @@ impl Sphere {
             material: Some(material),
         }
     }
+
+    // u goes around the sphere starting from -x axis
+    // and v goes from bottom(-y) to top(+y)
+    fn uv(&self, p: Vec3) -> (f64, f64) {
+        let d = (p - self.center) / self.radius.abs();
+        let phi = (-d.z()).atan2(d.x()) + std::f64::consts::PI;
+        let theta = (-d.y()).clamp(-1.0, 1.0).acos();
+
+        (
+            phi / (2.0 * std::f64::consts::PI),
+            theta / std::f64::consts::PI,
+        )
+    }
+
+    // Direction in which u increases, Horizontal and going around the y axis
+    fn tangent(&self, p: Vec3) -> Vec3 {
+        let d = p - self.center;
+        let tangent = Vec3::new(d.z(), 0.0, -d.x());
+        // u is undefined at the poles, Any horizontal vector will do
+        if tangent.sq_len() < 1e-12 {
+            Vec3::new(1.0, 0.0, 0.0)
+        } else {
+            tangent.unit_vector()
+        }
+    }
+
+    // How far p moves for a change in u(around the y axis) and v(from pole to pole)
+    fn derivatives(&self, p: Vec3) -> (Vec3, Vec3) {
+        let d = p - self.center;
+        let dpdu = Vec3::new(d.z(), 0.0, -d.x()) * (2.0 * std::f64::consts::PI);
+
+        let n = d / self.radius.abs();
+        let up = Vec3::new(0.0, 1.0, 0.0) - n * n.y();
+        let dpdv = if up.sq_len() < 1e-12 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            up.unit_vector() * (std::f64::consts::PI * self.radius.abs())
+        };
+        (dpdu, dpdv)
+    }
 }
 
 impl Hitable for Sphere {
@@ -43,10 +83,17 @@ impl Hitable for Sphere {
             let root = (-b - discriminant_root) / a;
             if root < t_max && root > t_min {
                 let p = ray.point_at_parameter(root);
+                let (u, v) = self.uv(p);
+                let (dpdu, dpdv) = self.derivatives(p);
                 return Some(HitRecord {
                     t: root,
                     p,
                     normal: (p - self.center) / self.radius,
+                    tangent: self.tangent(p),
+                    u,
+                    v,
+                    dpdu,
+                    dpdv,
                     material: self.material.as_ref(),
                 });
             }
@@ -54,11 +101,17 @@ impl Hitable for Sphere {
             let root = (-b + discriminant_root) / a;
             if root < t_max && root > t_min {
                 let p = ray.point_at_parameter(root);
-
+                let (u, v) = self.uv(p);
+                let (dpdu, dpdv) = self.derivatives(p);
                 return Some(HitRecord {
                     t: root,
                     p,
                     normal: (p - self.center) / self.radius,
+                    tangent: self.tangent(p),
+                    u,
+                    v,
+                    dpdu,
+                    dpdv,
                     material: self.material.as_ref(),
                 });
             }
@@ -0,0 +1,324 @@
+use {
+    crate::types::{sampler::SeededRng, Vec3},
+    rand::{seq::SliceRandom, Rng},
+};
+
+/// Anything that varies over a surface. (u, v) are the surface coordinates
+/// of the hit point and p is the hit point itself
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: Vec3) -> Vec3;
+}
+
+pub struct ConstantTexture {
+    color: Vec3,
+}
+
+impl ConstantTexture {
+    pub fn new(color: Vec3) -> Self {
+        Self { color }
+    }
+}
+
+impl Texture for ConstantTexture {
+    fn value(&self, _u: f64, _v: f64, _p: Vec3) -> Vec3 {
+        self.color
+    }
+}
+
+/// Linear blend from `bottom` at v = 0 to `top` at v = 1
+pub struct GradientTexture {
+    bottom: Vec3,
+    top: Vec3,
+}
+
+impl GradientTexture {
+    pub fn new(bottom: Vec3, top: Vec3) -> Self {
+        Self { bottom, top }
+    }
+}
+
+impl Texture for GradientTexture {
+    fn value(&self, _u: f64, v: f64, _p: Vec3) -> Vec3 {
+        self.bottom * (1.0 - v) + self.top * v
+    }
+}
+
+/// 3D checker pattern. Alternates between `odd` and `even` every 1 / `scale` units in space
+pub struct CheckerTexture {
+    odd: Vec3,
+    even: Vec3,
+    scale: f64,
+}
+
+impl CheckerTexture {
+    pub fn new(odd: Vec3, even: Vec3, scale: f64) -> Self {
+        Self { odd, even, scale }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, _u: f64, _v: f64, p: Vec3) -> Vec3 {
+        let k = self.scale * std::f64::consts::PI;
+        let sines = (k * p.x()).sin() * (k * p.y()).sin() * (k * p.z()).sin();
+        if sines < 0.0 {
+            self.odd
+        } else {
+            self.even
+        }
+    }
+}
+
+/// Texture backed by an image. The image covers the whole 0..1 uv range
+/// and wraps around outside of it, Both when looking up and when blending pixels
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    /// Row major, top row first. Each pixel is a RGB color in 0..1 range
+    pixels: Vec<Vec3>,
+}
+
+impl ImageTexture {
+    /// Decodes a binary(P6) or plain text(P3) PPM image with a max value of 255
+    pub fn from_ppm(data: &[u8]) -> Result<Self, String> {
+        // Header is magic number, width, height and the max value separated by whitespace.
+        // Comments start with # and go till the end of the line
+        let mut fields = Vec::with_capacity(4);
+        let mut position = 0;
+        while fields.len() < 4 {
+            match data.get(position) {
+                Some(b'#') => {
+                    while matches!(data.get(position), Some(&c) if c != b'\n') {
+                        position += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => position += 1,
+                Some(_) => {
+                    let start = position;
+                    while matches!(data.get(position), Some(c) if !c.is_ascii_whitespace()) {
+                        position += 1;
+                    }
+                    fields.push(String::from_utf8_lossy(&data[start..position]).to_string());
+                }
+                None => return Err("unexpected end of ppm header".to_string()),
+            }
+        }
+
+        let parse = |s: &str| {
+            s.parse::<usize>()
+                .map_err(|e| format!("invalid ppm header field {}: {}", s, e))
+        };
+        let width = parse(&fields[1])?;
+        let height = parse(&fields[2])?;
+        if width == 0 || height == 0 {
+            return Err("ppm image has no pixels".to_string());
+        }
+        let size = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or("ppm image is too large")?;
+        if parse(&fields[3])? != 255 {
+            return Err("only ppm images with max value of 255 are supported".to_string());
+        }
+
+        let samples: Vec<u8> = match fields[0].as_str() {
+            // Exactly one whitespace character separates the header from the pixel data
+            "P6" => data
+                .get(position + 1..)
+                .ok_or("missing ppm pixel data")?
+                .to_vec(),
+            "P3" => String::from_utf8_lossy(&data[position..])
+                .split_ascii_whitespace()
+                .map(|s| s.parse::<u8>().map_err(|e| e.to_string()))
+                .collect::<Result<_, _>>()?,
+            magic => return Err(format!("unsupported ppm format {}", magic)),
+        };
+        if samples.len() < size {
+            return Err("ppm image has less pixels than the header says".to_string());
+        }
+
+        let pixels = samples
+            .chunks(3)
+            .take(width * height)
+            .map(|c| Vec3::new(c[0] as f64, c[1] as f64, c[2] as f64) / 255.0)
+            .collect();
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+impl Texture for ImageTexture {
+    // Blends the four closest pixels, So the texture changes smoothly from one pixel to the
+    // next and height maps have a slope bump mapping can pick up
+    fn value(&self, u: f64, v: f64, _p: Vec3) -> Vec3 {
+        // v = 0 is the bottom of the image. Pixel centers are at half pixel offsets
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+
+        let i = (x.floor() as i64).rem_euclid(self.width as i64) as usize;
+        let j = (y.floor() as i64).rem_euclid(self.height as i64) as usize;
+        let pixel =
+            |i: usize, j: usize| self.pixels[(j % self.height) * self.width + (i % self.width)];
+
+        let top = pixel(i, j) * (1.0 - fx) + pixel(i + 1, j) * fx;
+        let bottom = pixel(i, j + 1) * (1.0 - fx) + pixel(i + 1, j + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+/// Perlin noise turbulence. Solid texture, It only depends on the point in space
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: f64,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f64, seed: u64) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Vec3) -> Vec3 {
+        let t = self.noise.turbulence(p * self.scale, 7);
+        Vec3::new(t, t, t)
+    }
+}
+
+const POINT_COUNT: usize = 256;
+
+/// Ken Perlin's gradient noise.
+/// See https://raytracing.github.io/books/RayTracingTheNextWeek.html#perlinnoise
+pub struct Perlin {
+    random_vectors: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    /// The same seed always gives the same noise
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SeededRng::new(seed);
+        let random_vectors = (0..POINT_COUNT)
+            .map(|_| {
+                Vec3::new(
+                    rng.gen_range(-1.0, 1.0),
+                    rng.gen_range(-1.0, 1.0),
+                    rng.gen_range(-1.0, 1.0),
+                )
+                .unit_vector()
+            })
+            .collect();
+
+        Self {
+            random_vectors,
+            perm_x: permutation(&mut rng),
+            perm_y: permutation(&mut rng),
+            perm_z: permutation(&mut rng),
+        }
+    }
+
+    /// Smooth noise in -1..1 range
+    pub fn noise(&self, p: Vec3) -> f64 {
+        let (u, v, w) = (
+            p.x() - p.x().floor(),
+            p.y() - p.y().floor(),
+            p.z() - p.z().floor(),
+        );
+        let (i, j, k) = (
+            p.x().floor() as i64,
+            p.y().floor() as i64,
+            p.z().floor() as i64,
+        );
+
+        // hermite smoothing to get rid of the grid artifacts
+        let (uu, vv, ww) = (
+            u * u * (3.0 - 2.0 * u),
+            v * v * (3.0 - 2.0 * v),
+            w * w * (3.0 - 2.0 * w),
+        );
+
+        let mut accum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.perm_x[((i + di) & 255) as usize]
+                        ^ self.perm_y[((j + dj) & 255) as usize]
+                        ^ self.perm_z[((k + dk) & 255) as usize];
+                    let (fi, fj, fk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Vec3::new(u - fi, v - fj, w - fk);
+
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * self.random_vectors[index].dot(&weight);
+                }
+            }
+        }
+        accum
+    }
+
+    /// Sum of `depth` octaves of noise
+    pub fn turbulence(&self, p: Vec3, depth: usize) -> f64 {
+        let mut accum = 0.0;
+        let mut p = p;
+        let mut weight = 1.0;
+        for _ in 0..depth {
+            accum += weight * self.noise(p);
+            weight *= 0.5;
+            p *= 2.0;
+        }
+        accum.abs()
+    }
+}
+
+fn permutation(rng: &mut impl Rng) -> Vec<usize> {
+    let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+    p.shuffle(rng);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageTexture, Texture};
+    use crate::types::Vec3;
+
+    #[test]
+    fn valid() {
+        let texture = ImageTexture::from_ppm(b"P6 1 1 255\n\xff\x00\x33").unwrap();
+        let color = texture.value(0.5, 0.5, Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!((color.r(), color.g(), color.b()), (1.0, 0.0, 0.2));
+
+        let texture =
+            ImageTexture::from_ppm(b"P3\n# two pixels\n2 1 255\n0 0 0 255 255 255").unwrap();
+        let halfway = texture.value(0.5, 0.5, Vec3::new(0.0, 0.0, 0.0));
+        assert!((halfway.r() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(ImageTexture::from_ppm(b"P6 1 1").is_err());
+        assert!(ImageTexture::from_ppm(b"P6 1 1 255").is_err());
+        assert!(ImageTexture::from_ppm(b"P6 2 1 255\nabc").is_err());
+        assert!(ImageTexture::from_ppm(b"P3 1 1 255\n1 2").is_err());
+    }
+
+    #[test]
+    fn zero_sizes() {
+        assert!(ImageTexture::from_ppm(b"P6 0 1 255\n").is_err());
+        assert!(ImageTexture::from_ppm(b"P3 0 0 255\n").is_err());
+    }
+
+    #[test]
+    fn overflowing_sizes() {
+        assert!(ImageTexture::from_ppm(b"P6 99999999999 99999999999 255\n").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::types::Vec3;
+
+/// 3D grid of densities, e.g. a cloud or a smoke simulation cache.
+/// Densities are looked up with coordinates in 0..1 range along each axis
+/// and are interpolated between the voxels.
+pub struct VoxelGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    /// x varies fastest, then y, then z
+    data: Vec<f64>,
+    max: f64,
+}
+
+impl VoxelGrid {
+    /// Fills a grid of `nx * ny * nz` voxels by calling `density` with the center of each voxel.
+    /// Panics if any of the sizes is 0
+    pub fn from_fn(nx: usize, ny: usize, nz: usize, density: impl Fn(Vec3) -> f64) -> Self {
+        assert!(
+            nx > 0 && ny > 0 && nz > 0,
+            "voxel grid needs at least one voxel along each axis"
+        );
+        let mut data = Vec::with_capacity(nx * ny * nz);
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    let p = Vec3::new(
+                        (i as f64 + 0.5) / nx as f64,
+                        (j as f64 + 0.5) / ny as f64,
+                        (k as f64 + 0.5) / nz as f64,
+                    );
+                    data.push(density(p).max(0.0));
+                }
+            }
+        }
+
+        Self::with_data(nx, ny, nz, data)
+    }
+
+    /// Decodes a 3D NRRD volume with raw encoding. Samples can be `uint8`, Which are
+    /// mapped to 0..1 range, or little endian `float`s which are used as is
+    pub fn from_nrrd(data: &[u8]) -> Result<Self, String> {
+        if !data.starts_with(b"NRRD") {
+            return Err("not a nrrd file".to_string());
+        }
+
+        // Header is "field: value" lines and ends with an empty line.
+        // Comments start with #
+        let mut position = 0;
+        let mut kind = None;
+        let mut sizes = None;
+        let mut raw = false;
+        let mut big_endian = false;
+        loop {
+            let end = match data[position..].iter().position(|&c| c == b'\n') {
+                Some(end) => position + end,
+                None => return Err("unexpected end of nrrd header".to_string()),
+            };
+            let line = String::from_utf8_lossy(&data[position..end]);
+            let line = line.trim();
+            position = end + 1;
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with('#') || line.starts_with("NRRD") {
+                continue;
+            }
+
+            let (field, value) = match line.find(':') {
+                Some(i) => (
+                    line[..i].trim(),
+                    line[i + 1..].trim_start_matches('=').trim(),
+                ),
+                None => return Err(format!("invalid nrrd header line {}", line)),
+            };
+            match field {
+                "type" => kind = Some(value.to_string()),
+                "dimension" if value != "3" => {
+                    return Err("only 3 dimensional nrrd volumes are supported".to_string())
+                }
+                "sizes" => {
+                    sizes = Some(
+                        value
+                            .split_ascii_whitespace()
+                            .map(|s| s.parse::<usize>().map_err(|e| e.to_string()))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                "encoding" => raw = value == "raw",
+                "endian" => big_endian = value == "big",
+                _ => (),
+            }
+        }
+
+        let sizes = match sizes {
+            Some(sizes) if sizes.len() == 3 => sizes,
+            _ => return Err("nrrd header is missing the sizes of the volume".to_string()),
+        };
+        if !raw {
+            return Err("only raw encoded nrrd files are supported".to_string());
+        }
+        if sizes.contains(&0) {
+            return Err("nrrd volume has no voxels".to_string());
+        }
+        let count = sizes[0]
+            .checked_mul(sizes[1])
+            .and_then(|count| count.checked_mul(sizes[2]))
+            .ok_or("nrrd volume is too large")?;
+        let samples = &data[position..];
+
+        let values: Vec<f64> = match kind.as_deref() {
+            Some("uint8") | Some("uchar") | Some("unsigned char") => samples
+                .iter()
+                .take(count)
+                .map(|&v| v as f64 / 255.0)
+                .collect(),
+            Some("float") if !big_endian => samples
+                .chunks_exact(4)
+                .take(count)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64)
+                .collect(),
+            Some(kind) => return Err(format!("unsupported nrrd sample type {}", kind)),
+            None => return Err("nrrd header is missing the sample type".to_string()),
+        };
+        if values.len() < count {
+            return Err("nrrd file has less samples than the header says".to_string());
+        }
+
+        Ok(Self::with_data(
+            sizes[0],
+            sizes[1],
+            sizes[2],
+            values.into_iter().map(|v| v.max(0.0)).collect(),
+        ))
+    }
+
+    fn with_data(nx: usize, ny: usize, nz: usize, data: Vec<f64>) -> Self {
+        let max = data.iter().fold(0.0f64, |max, &d| max.max(d));
+        Self {
+            nx,
+            ny,
+            nz,
+            data,
+            max,
+        }
+    }
+
+    /// Largest density anywhere in the grid
+    pub fn max_density(&self) -> f64 {
+        self.max
+    }
+
+    /// Trilinearly interpolated density at `p`. Zero outside the 0..1 range
+    pub fn density(&self, p: Vec3) -> f64 {
+        if (0..3).any(|axis| p[axis] < 0.0 || p[axis] > 1.0) {
+            return 0.0;
+        }
+
+        // Voxel centers are at (i + 0.5) / n
+        let x = (p.x() * self.nx as f64 - 0.5).max(0.0);
+        let y = (p.y() * self.ny as f64 - 0.5).max(0.0);
+        let z = (p.z() * self.nz as f64 - 0.5).max(0.0);
+        let (i, j, k) = (x as usize, y as usize, z as usize);
+        let (tx, ty, tz) = (x - i as f64, y - j as f64, z - k as f64);
+
+        let at = |i: usize, j: usize, k: usize| {
+            let i = i.min(self.nx - 1);
+            let j = j.min(self.ny - 1);
+            let k = k.min(self.nz - 1);
+            self.data[(k * self.ny + j) * self.nx + i]
+        };
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let c00 = lerp(at(i, j, k), at(i + 1, j, k), tx);
+        let c10 = lerp(at(i, j + 1, k), at(i + 1, j + 1, k), tx);
+        let c01 = lerp(at(i, j, k + 1), at(i + 1, j, k + 1), tx);
+        let c11 = lerp(at(i, j + 1, k + 1), at(i + 1, j + 1, k + 1), tx);
+
+        lerp(lerp(c00, c10, ty), lerp(c01, c11, ty), tz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VoxelGrid;
+
+    fn nrrd(sizes: &str, samples: &[u8]) -> Vec<u8> {
+        let mut data = format!(
+            "NRRD0004\ntype: uint8\ndimension: 3\nsizes: {}\nencoding: raw\n\n",
+            sizes
+        )
+        .into_bytes();
+        data.extend_from_slice(samples);
+        data
+    }
+
+    #[test]
+    fn valid() {
+        let grid = VoxelGrid::from_nrrd(&nrrd("2 1 1", &[0, 255])).unwrap();
+        assert_eq!(grid.max_density(), 1.0);
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(VoxelGrid::from_nrrd(&nrrd("2 2 2", &[0, 255])).is_err());
+        assert!(VoxelGrid::from_nrrd(b"NRRD0004\ntype: uint8").is_err());
+    }
+
+    #[test]
+    fn zero_sizes() {
+        assert!(VoxelGrid::from_nrrd(&nrrd("0 1 1", &[])).is_err());
+    }
+
+    #[test]
+    fn overflowing_sizes() {
+        let huge = usize::MAX / 2;
+        assert!(VoxelGrid::from_nrrd(&nrrd(&format!("{} {} 2", huge, huge), &[0])).is_err());
+    }
+}